@@ -38,6 +38,11 @@ fn get_attr_builder(field:& syn::Field) ->Option<&syn::Attribute> {
     attrs.iter().find(|&attr| attr.path.segments.len() == 1 && attr.path.segments[0].ident == "builder")
 }
 
+// /// doc comments are stored as #[doc = "..."]
+fn get_attrs_doc(field:& syn::Field) -> Vec<&syn::Attribute> {
+    field.attrs.iter().filter(|&attr| attr.path.is_ident("doc")).collect()
+}
+
 // Option<String> => Some(String)
 fn inner_type<'a>(outer:&'a str, ty:&'a syn::Type) -> Option<&'a syn::Type> {
     if let syn::Type::Path(syn::TypePath { qself: None, ref path,}) = ty {
@@ -98,12 +103,14 @@ pub fn derive(input: TokenStream) -> TokenStream {
     let builder_fields_setters = fields.iter().filter_map(|f| {
         let ident = &f.ident;
         let ty = &&f.ty;
+        let docs = get_attrs_doc(f);
         let inner_ty = inner_type("Option", ty);
         if get_attr_builder(f).is_some() {
             return None;
         }
         if let Some(inner_ty) = inner_ty {
             Some(quote!(
+                #(#docs)*
                 pub fn #ident(&mut self, #ident: #inner_ty) -> &mut Self {
                     self.#ident = Some(#ident);
                     self
@@ -111,6 +118,7 @@ pub fn derive(input: TokenStream) -> TokenStream {
             ))
         } else {
             Some(quote!(
+                #(#docs)*
                 pub fn #ident(&mut self, #ident: #ty) -> &mut Self {
                     self.#ident = Some(#ident);
                     self
@@ -122,11 +130,13 @@ pub fn derive(input: TokenStream) -> TokenStream {
     let builder_vecs_setters = fields.iter().filter_map(|f| {
         let ident = &f.ident;
         let ty = &f.ty;
+        let docs = get_attrs_doc(f);
         if let Some(attr) = get_attr_builder(f) {
             let indent = get_name_value("each", attr);
             if let Ok(each) = indent {
                 let inner_ty = inner_type("Vec", ty).unwrap();
                 Some(quote!(
+                    #(#docs)*
                     pub fn #each(&mut self, #each: #inner_ty) -> &mut Self {
                         self.#ident.push(#each);
                         self
@@ -154,7 +164,37 @@ pub fn derive(input: TokenStream) -> TokenStream {
         }
     });
 
+    // Required fields: `a`, `b`.
+    // Defaulted fields:
+    // - `c`: `None`
+    let mut required_fields = vec![];
+    let mut defaulted_fields = vec![];
+    for f in &fields {
+        let name = f.ident.as_ref().unwrap().to_string();
+        if get_attr_builder(f).is_some() {
+            defaulted_fields.push(format!("- `{}`: `Vec::new()`", name));
+        } else if inner_type("Option", &f.ty).is_some() {
+            defaulted_fields.push(format!("- `{}`: `None`", name));
+        } else {
+            required_fields.push(format!("`{}`", name));
+        }
+    }
+    let mut builder_docs = vec![format!(" Builder for [`{}`].", derived_obj_ident)];
+    if !required_fields.is_empty() {
+        builder_docs.push(String::new());
+        builder_docs.push(format!(" Required fields: {}.", required_fields.join(", ")));
+    }
+    if !defaulted_fields.is_empty() {
+        builder_docs.push(String::new());
+        builder_docs.push(" Defaulted fields:".to_owned());
+        builder_docs.extend(defaulted_fields.iter().map(|line| format!(" {}", line)));
+    }
+
+    let builder_doc = format!(" Creates a [`{}`] with every field unset.", derived_obj_builder_ident);
+    let build_doc = format!(" Builds the [`{}`], failing if a required field was never set.", derived_obj_ident);
+
     let output = quote!(
+        #(#[doc = #builder_docs])*
         pub struct #derived_obj_builder_ident {
             #(#builder_fields_declare),
             *
@@ -163,6 +203,7 @@ pub fn derive(input: TokenStream) -> TokenStream {
         impl #derived_obj_builder_ident {
             #(#builder_fields_setters)*
             #(#builder_vecs_setters)*
+            #[doc = #build_doc]
             pub fn build(&mut self) -> std::result::Result<#derived_obj_ident, ::std::boxed::Box<dyn ::std::error::Error>> {
                 std::result::Result::Ok(
                     #derived_obj_ident {
@@ -173,6 +214,7 @@ pub fn derive(input: TokenStream) -> TokenStream {
         }
         
         impl #derived_obj_ident {
+            #[doc = #builder_doc]
             pub fn builder() -> #derived_obj_builder_ident {
                 #derived_obj_builder_ident {
                     #(#builder_fields_default),
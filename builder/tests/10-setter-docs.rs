@@ -0,0 +1,40 @@
+// Doc comments on the fields of the input struct should follow each field onto
+// its generated setter, including the one-at-a-time setter of a repeated
+// field, so that rustdoc for a public builder is not empty. The builder struct
+// itself gets a generated doc listing which fields must be set and what the
+// others default to.
+//
+// Everything the macro emits here is public, so missing_docs catches any
+// generated item that was left undocumented.
+
+#![deny(missing_docs)]
+
+//! Command builder.
+
+use derive_builder::Builder;
+
+/// A command to run.
+#[derive(Builder)]
+pub struct Command {
+
+    /// Program to execute.
+    executable: String,
+    /// Arguments passed to the program.
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    /// Working directory, if not the current one.
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+}
@@ -0,0 +1,158 @@
+use quote::{format_ident, quote};
+use syn::{Fields, parse_quote};
+use syn::ext::IdentExt;
+
+use crate::{add_trait_bounds, fields_pattern, parse_format_string, struct_bindings, FormatPiece};
+
+// #[display("{name} ({id:#x})")]
+fn get_display_template(attrs:&[syn::Attribute]) -> Result<Option<syn::LitStr>, syn::__private::TokenStream2> {
+    let attr = match attrs.iter().find(|&attr| attr.path.is_ident("display")) {
+        Some(attr) => attr,
+        None => return Ok(None),
+    };
+    match attr.parse_meta() {
+        Ok(syn::Meta::List(list)) if list.nested.len() == 1 => match &list.nested[0] {
+            syn::NestedMeta::Lit(syn::Lit::Str(lit)) => Ok(Some(lit.clone())),
+            nested => Err(syn::Error::new_spanned(nested, "expected `display(\"...\")`").to_compile_error()),
+        },
+        _ => Err(syn::Error::new_spanned(attr, "expected `display(\"...\")`").to_compile_error()),
+    }
+}
+
+// {:?} needs Debug, {:x} needs LowerHex, and so on
+fn spec_trait(spec:&str) -> &'static str {
+    match spec.chars().last() {
+        Some('?') => "Debug",
+        Some('x') => "LowerHex",
+        Some('X') => "UpperHex",
+        Some('b') => "Binary",
+        Some('o') => "Octal",
+        Some('e') => "LowerExp",
+        Some('E') => "UpperExp",
+        _ => "Display",
+    }
+}
+
+// field type and the name of the std::fmt trait it is formatted with
+type FieldBounds<'a> = Vec<(&'a syn::Type, &'static str)>;
+
+// Rewrites every `{name:spec}` into `{__fieldN:spec}` and passes the fields
+// it refers to as named arguments of write!. Returns the write! call together
+// with the types of the fields it used.
+fn template_fmt<'a>(
+    lit:&syn::LitStr,
+    fields:&'a Fields,
+    bindings:&[syn::__private::TokenStream2],
+) -> Result<(syn::__private::TokenStream2, FieldBounds<'a>), syn::__private::TokenStream2> {
+    let error = |msg:String| syn::Error::new_spanned(lit, msg).to_compile_error();
+    let pieces = parse_format_string(&lit.value()).map_err(|msg| error(msg.to_owned()))?;
+
+    let mut template = String::new();
+    let mut used = vec![];
+    let mut bounds = vec![];
+    for piece in pieces {
+        let (arg, spec) = match piece {
+            FormatPiece::Literal(literal) => {
+                template.push_str(&literal);
+                continue;
+            }
+            FormatPiece::Placeholder { arg, spec } => (arg, spec),
+        };
+        let index = if arg.is_empty() {
+            return Err(error("placeholders in a display template must name a field, like `{name}` or `{0}`".to_owned()));
+        } else if let Ok(index) = arg.parse::<usize>() {
+            Some(index).filter(|&index| matches!(fields, Fields::Unnamed(_)) && index < fields.len())
+        } else {
+            fields.iter().position(|f| f.ident.as_ref().is_some_and(|ident| ident.unraw() == arg))
+        };
+        let index = index.ok_or_else(|| error(format!("no field `{}` to display", arg)))?;
+
+        let field = fields.iter().nth(index).unwrap();
+        bounds.push((&field.ty, spec_trait(&spec)));
+        if !used.contains(&index) {
+            used.push(index);
+        }
+        template.push_str(&format!("{{__field{}:{}}}", index, spec));
+    }
+
+    let names = used.iter().map(|i| format_ident!("__field{}", i));
+    let values = used.iter().map(|&i| &bindings[i]);
+    let write = quote!(write!(fmt, #template, #(#names = #values),*));
+    Ok((write, bounds))
+}
+
+pub(crate) fn expand(ast:&syn::DeriveInput) -> Result<syn::__private::TokenStream2, syn::__private::TokenStream2> {
+    let deriver_ident = &ast.ident;
+    let mut bounds = vec![];
+
+    let body = match &ast.data {
+        syn::Data::Struct(ds) => {
+            let template = get_display_template(&ast.attrs)?.ok_or_else(|| {
+                syn::Error::new_spanned(deriver_ident, "derive(CustomDisplay) requires a `#[display(\"...\")]` template").to_compile_error()
+            })?;
+            let (write, used) = template_fmt(&template, &ds.fields, &struct_bindings(&ds.fields))?;
+            bounds.extend(used);
+            write
+        }
+        syn::Data::Enum(de) => {
+            if let Some(template) = get_display_template(&ast.attrs)? {
+                return Err(syn::Error::new_spanned(template, "enums take a `#[display(\"...\")]` template on each variant").to_compile_error());
+            }
+            let mut arms = vec![];
+            for v in &de.variants {
+                let variant_ident = &v.ident;
+                let (pattern, bindings) = fields_pattern(quote!(Self::#variant_ident), &v.fields);
+                let fmt = match get_display_template(&v.attrs)? {
+                    Some(template) => {
+                        let (write, used) = template_fmt(&template, &v.fields, &bindings)?;
+                        bounds.extend(used);
+                        write
+                    }
+                    None if v.fields.is_empty() => {
+                        let variant_literal = variant_ident.unraw().to_string();
+                        quote!(fmt.write_str(#variant_literal))
+                    }
+                    None => {
+                        return Err(syn::Error::new_spanned(v, "variants with fields require a `#[display(\"...\")]` template").to_compile_error());
+                    }
+                };
+                arms.push(quote!(#pattern => #fmt,));
+            }
+            if arms.is_empty() {
+                quote!(match *self {})
+            } else {
+                quote!(
+                    match self {
+                        #(#arms)*
+                    }
+                )
+            }
+        }
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new_spanned(deriver_ident, "derive(CustomDisplay) doesn't support unions").to_compile_error());
+        }
+    };
+
+    // one pass per trait, so each type parameter gets each bound once
+    let mut generics_new = ast.generics.clone();
+    let mut traits = vec![];
+    for &(_, bound) in &bounds {
+        if !traits.contains(&bound) {
+            traits.push(bound);
+        }
+    }
+    for bound in traits {
+        let tys: Vec<_> = bounds.iter().filter(|&&(_, b)| b == bound).map(|&(ty, _)| ty).collect();
+        let bound = format_ident!("{}", bound);
+        add_trait_bounds(&mut generics_new, &tys, &parse_quote!(std::fmt::#bound));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics_new.split_for_impl();
+
+    Ok(quote!(
+        impl #impl_generics std::fmt::Display for #deriver_ident #ty_generics #where_clause {
+            fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+                #body
+            }
+        }
+    ))
+}
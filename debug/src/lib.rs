@@ -1,140 +1,999 @@
-#![feature(trace_macros)]
-
 use proc_macro::TokenStream;
-use quote::{quote};
-use syn::{Fields, parse_quote, Type, TypePath};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{Fields, parse_quote};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+mod display;
 
-fn get_name_value(name:&str, attr:&syn::Attribute) -> Result<String, syn::__private::TokenStream2>{
+fn get_name_value(name:&str, attr:&syn::Attribute) -> Result<syn::LitStr, syn::__private::TokenStream2>{
     match attr.parse_meta() {
         Ok(syn::Meta::NameValue(nv)) => {
             if !nv.path.is_ident(name) {
-                Err(syn::Error::new_spanned(nv, "expected `debug = \"...\")`").to_compile_error())
+                Err(syn::Error::new_spanned(nv, "expected `debug = \"...\"`").to_compile_error())
             } else {
                 match &nv.lit {
                     syn::Lit::Str(val) => {
-                        Ok(val.value())
+                        Ok(val.clone())
                     },
                     _ => {
-                        Err(syn::Error::new_spanned(nv, "expected `debug = \"...\")`").to_compile_error())
+                        Err(syn::Error::new_spanned(nv, "expected `debug = \"...\"`").to_compile_error())
                     }
                 }
             }
         }
         _ => {
-            Err(syn::Error::new_spanned(attr, "expected `builder(each = \"...\")`").to_compile_error())
+            Err(syn::Error::new_spanned(attr, "expected `debug = \"...\"`").to_compile_error())
         }
     }
 }
 
 // #[debug="0b{:08b}"]
-fn get_attr_debug(field:& syn::Field) ->Option<&syn::Attribute> {
-    let attrs = &field.attrs;
-    attrs.iter().find(|&attr| attr.path.segments.len() == 1 && attr.path.segments[0].ident == "debug")
-}
-
-fn get_phantomdata_generic_type_name(field:&syn::Field) -> syn::Result<Option<String>>{
-    if let Type::Path(TypePath{path: syn::Path { ref segments,..}, ..}) = field.ty {
-        if let Some(syn::PathSegment{ref ident, ref arguments}) = segments.first() {
-            if ident == "PhantomData" {
-                if let syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments{args, ..}) = arguments {
-                    if let Some(syn::GenericArgument::Type(syn::Type::Path(ref gp))) = args.first() {
-                        if let Some(generic_ident) = gp.path.segments.first() {
-                            return Ok(Some(generic_ident.ident.to_string()));
-                        }
+fn get_attrs_debug(attrs:&[syn::Attribute]) -> impl Iterator<Item = &syn::Attribute> {
+    attrs.iter().filter(|&attr| attr.path.segments.len() == 1 && attr.path.segments[0].ident == "debug")
+}
+
+fn get_lit_usize(nv:&syn::MetaNameValue) -> Result<usize, syn::__private::TokenStream2> {
+    match &nv.lit {
+        syn::Lit::Int(val) => val.base10_parse().map_err(|e| e.to_compile_error()),
+        _ => Err(syn::Error::new_spanned(&nv.lit, "expected integer literal").to_compile_error()),
+    }
+}
+
+fn get_lit_str(nv:&syn::MetaNameValue) -> Result<&syn::LitStr, syn::__private::TokenStream2> {
+    match &nv.lit {
+        syn::Lit::Str(val) => Ok(val),
+        _ => Err(syn::Error::new_spanned(&nv.lit, "expected string literal").to_compile_error()),
+    }
+}
+
+enum FormatPiece {
+    // literal text, with `{{` and `}}` left escaped
+    Literal(String),
+    // {arg:spec}
+    Placeholder { arg: String, spec: String },
+}
+
+fn parse_format_string(fmt:&str) -> Result<Vec<FormatPiece>, &'static str> {
+    let mut pieces = vec![];
+    let mut literal = String::new();
+    let mut chars = fmt.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' if chars.peek() == Some(&c) => {
+                chars.next();
+                literal.push(c);
+                literal.push(c);
+            }
+            '}' => return Err("invalid format string: unmatched `}`, use `}}` for a literal brace"),
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err("invalid format string: unterminated `{`, use `{{` for a literal brace"),
                     }
                 }
+                let (arg, spec) = placeholder.split_once(':').unwrap_or((&placeholder, ""));
+                if spec.contains('$') || spec.contains(".*") {
+                    return Err("invalid format string: width and precision cannot be taken from arguments");
+                }
+                pieces.push(FormatPiece::Literal(std::mem::take(&mut literal)));
+                pieces.push(FormatPiece::Placeholder { arg: arg.trim().to_owned(), spec: spec.to_owned() });
             }
-        }        
+            c => literal.push(c),
+        }
     }
-    Ok(None)
+    pieces.push(FormatPiece::Literal(literal));
+    Ok(pieces)
 }
 
-fn get_field_type(field:&syn::Field) -> syn::Result<Option<String>> {
-    if let syn::Type::Path(syn::TypePath{path: syn::Path{ref segments, ..}, ..}) = field.ty {
-        if let Some(syn::PathSegment{ref ident,..}) = segments.last() {
-            return Ok(Some(ident.to_string()))
+// The format string is handed to format_args! together with a reference to the
+// field, so every placeholder has to refer to that one argument. Checking it
+// here points at the attribute instead of at the generated code.
+fn check_format_string(lit:&syn::LitStr) -> Result<(), syn::__private::TokenStream2> {
+    let error = |msg:&str| Err(syn::Error::new_spanned(lit, msg).to_compile_error());
+    let pieces = match parse_format_string(&lit.value()) {
+        Ok(pieces) => pieces,
+        Err(msg) => return error(msg),
+    };
+    let mut next_implicit = 0;
+    let mut placeholders = 0;
+    for piece in pieces {
+        if let FormatPiece::Placeholder { arg, .. } = piece {
+            let index = match arg.as_str() {
+                "" => {
+                    next_implicit += 1;
+                    next_implicit - 1
+                }
+                arg => match arg.parse::<usize>() {
+                    Ok(index) => index,
+                    Err(_) => return error("invalid format string: named arguments are not available, use `{}` or `{0}` for the field"),
+                },
+            };
+            if index != 0 {
+                return error("invalid format string: expected exactly one argument, the field");
+            }
+            placeholders += 1;
         }
     }
-    Ok(None)
+    if placeholders == 0 {
+        return error("invalid format string: expected a `{}` placeholder for the field");
+    }
+    Ok(())
 }
 
-#[proc_macro_derive(CustomDebug, attributes(debug))]
-pub fn derive(input: TokenStream) -> TokenStream {
-    let ast = syn::parse_macro_input!(input as syn::DeriveInput);
-    let deriver_ident = &ast.ident;
-    let deriver_literal = deriver_ident.to_string();
-    
-    let fields = match ast.data {
-        syn::Data::Struct(ds) => {
-            if let Fields::Named(fs) = ds.fields {
-                fs.named
-            } else {
-                unimplemented!("derive(debug) doesn't support tuple structs")
+type WherePredicates = syn::punctuated::Punctuated<syn::WherePredicate, syn::Token![,]>;
+
+// #[debug(bound = "T::Value: Debug")]
+fn get_bound(nv:&syn::MetaNameValue) -> Result<WherePredicates, syn::__private::TokenStream2> {
+    let lit = get_lit_str(nv)?;
+    lit.parse_with(WherePredicates::parse_terminated).map_err(|e| {
+        syn::Error::new_spanned(lit, format!("invalid bound, expected where-predicates like `T: Debug`: {}", e)).to_compile_error()
+    })
+}
+
+// #[debug(rename_all = "camelCase")], with the same rules as serde: applied to
+// field names on structs and to variant names on enums
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn from_lit(lit:&syn::LitStr) -> Result<Self, syn::__private::TokenStream2> {
+        Ok(match lit.value().as_str() {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => {
+                return Err(syn::Error::new_spanned(lit, "unknown rename rule, expected one of \"lowercase\", \"UPPERCASE\", \"PascalCase\", \"camelCase\", \"snake_case\", \"SCREAMING_SNAKE_CASE\", \"kebab-case\", \"SCREAMING-KEBAB-CASE\"").to_compile_error());
             }
+        })
+    }
+
+    // field names are snake_case to begin with
+    fn apply_to_field(self, field:&str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_owned(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_uppercase(),
+            RenameRule::Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for c in field.chars() {
+                    if c == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.extend(c.to_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(c);
+                    }
+                }
+                pascal
+            }
+            RenameRule::Camel => {
+                let pascal = RenameRule::Pascal.apply_to_field(field);
+                let mut chars = pascal.chars();
+                chars.next().into_iter().flat_map(char::to_lowercase).chain(chars).collect()
+            }
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.to_uppercase().replace('_', "-"),
         }
-        _ => unimplemented!("derive(debug) only support structs"),
-    };
+    }
+
+    // variant names are PascalCase to begin with
+    fn apply_to_variant(self, variant:&str) -> String {
+        match self {
+            RenameRule::Pascal => variant.to_owned(),
+            RenameRule::Lower => variant.to_lowercase(),
+            RenameRule::Upper => variant.to_uppercase(),
+            RenameRule::Camel => {
+                let mut chars = variant.chars();
+                chars.next().into_iter().flat_map(char::to_lowercase).chain(chars).collect()
+            }
+            RenameRule::Snake => {
+                let mut snake = String::new();
+                for (i, c) in variant.char_indices() {
+                    if i > 0 && c.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.extend(c.to_lowercase());
+                }
+                snake
+            }
+            RenameRule::ScreamingSnake => RenameRule::Snake.apply_to_variant(variant).to_uppercase(),
+            RenameRule::Kebab => RenameRule::Snake.apply_to_variant(variant).replace('_', "-"),
+            RenameRule::ScreamingKebab => RenameRule::ScreamingSnake.apply_to_variant(variant).replace('_', "-"),
+        }
+    }
+}
+
+#[derive(Default)]
+struct ContainerOptions {
+    // #[debug(bound = "T::Value: Debug")]
+    bound: Option<WherePredicates>,
+    // #[debug(compact)]
+    compact: bool,
+    // #[debug(transparent)]
+    transparent: bool,
+    // #[debug(name = "Msg")]
+    name: Option<String>,
+    // #[debug(rename_all = "camelCase")]
+    rename_all: Option<RenameRule>,
+    // #[debug(trace)]
+    trace: bool,
+    // #[debug(union_as = "unsafe_bytes")]
+    union_as_bytes: bool,
+    // #[debug(also = "LowerHex, UpperHex, Binary")]
+    also: Vec<syn::Ident>,
+    // #[debug(visit_fields)]
+    visit_fields: bool,
+}
 
-    let mut field_type_names = vec![];
-    let mut phantomdata_type_names = vec![];
+// the std::fmt traits besides Debug that #[debug(also = "...")] can generate
+fn is_numeric_fmt_trait(fmt_trait:&syn::Ident) -> bool {
+    matches!(fmt_trait.to_string().as_str(), "LowerHex" | "UpperHex" | "Binary" | "Octal" | "LowerExp" | "UpperExp")
+}
 
-    for field in &fields {
-        if let Ok(Some(n)) = get_phantomdata_generic_type_name(field) {
-            phantomdata_type_names.push(n);
-        } else if let Ok(Some(n)) = get_field_type(field) {
-            field_type_names.push(n);
+fn get_container_options(attrs:&[syn::Attribute]) -> Result<ContainerOptions, syn::__private::TokenStream2> {
+    let mut options = ContainerOptions::default();
+    for attr in get_attrs_debug(attrs) {
+        let list = match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => list,
+            _ => return Err(syn::Error::new_spanned(attr, "expected `debug(...)`").to_compile_error()),
+        };
+        for nested in &list.nested {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("bound") => {
+                    options.bound = Some(get_bound(nv)?);
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("compact") => {
+                    options.compact = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("transparent") => {
+                    options.transparent = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("union_as") => {
+                    let lit = get_lit_str(nv)?;
+                    match lit.value().as_str() {
+                        "unsafe_bytes" => options.union_as_bytes = true,
+                        "bytes" => {
+                            return Err(syn::Error::new_spanned(lit, "reading a union's bytes is only sound if all of them are initialized, spell it `union_as = \"unsafe_bytes\"` to vouch for that").to_compile_error());
+                        }
+                        _ => return Err(syn::Error::new_spanned(lit, "expected `union_as = \"unsafe_bytes\"`").to_compile_error()),
+                    }
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("also") => {
+                    let lit = get_lit_str(nv)?;
+                    let traits = lit.parse_with(syn::punctuated::Punctuated::<syn::Ident, syn::Token![,]>::parse_terminated)
+                        .map_err(|e| e.to_compile_error())?;
+                    for fmt_trait in traits {
+                        if !is_numeric_fmt_trait(&fmt_trait) {
+                            return Err(syn::Error::new_spanned(lit, format!("cannot generate `{}`, expected LowerHex, UpperHex, Binary, Octal, LowerExp or UpperExp", fmt_trait)).to_compile_error());
+                        }
+                        options.also.push(fmt_trait);
+                    }
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("visit_fields") => {
+                    options.visit_fields = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("trace") => {
+                    options.trace = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("name") => {
+                    options.name = Some(get_lit_str(nv)?.value());
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("rename_all") => {
+                    options.rename_all = Some(RenameRule::from_lit(get_lit_str(nv)?)?);
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(nested, "unrecognized debug attribute").to_compile_error());
+                }
+            }
         }
     }
+    Ok(options)
+}
+
+#[derive(Default)]
+struct FieldOptions {
+    // #[debug = "0b{:08b}"]
+    format: Option<String>,
+    // #[debug(skip)]
+    skip: bool,
+    // #[debug(redact)], #[debug(redact = "***")]
+    redact: Option<String>,
+    // #[debug(redact_with = "mask")]
+    redact_with: Option<syn::Path>,
+    // #[debug(with = "path::to_fn")]
+    with: Option<syn::Path>,
+    // #[debug(bound = "T: Display")]
+    bound: Option<WherePredicates>,
+    // #[debug(rename = "msgType")]
+    rename: Option<String>,
+    // #[debug(max_items = 16)]
+    max_items: Option<usize>,
+    // #[debug(max_len = 256)]
+    max_len: Option<usize>,
+    // #[debug(hex)], #[debug(hexdump)], #[debug(base64)]
+    bytes: Option<ByteFormat>,
+}
+
+#[derive(Clone, Copy)]
+enum ByteFormat {
+    Hex,
+    HexDump,
+    Base64,
+}
 
-    // modify generics
-    let mut generics_new = ast.generics.clone();
-    for g in generics_new.params.iter_mut() {
-        if let syn::GenericParam::Type(t) = g {
-            let type_name = t.ident.to_string();
-            // add debug trait
-            if phantomdata_type_names.contains(&type_name)  && !field_type_names.contains(&type_name) {
+fn get_field_options(field:&syn::Field) -> Result<FieldOptions, syn::__private::TokenStream2> {
+    let mut options = FieldOptions::default();
+    for attr in get_attrs_debug(&field.attrs) {
+        let list = match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => list,
+            _ => {
+                let lit = get_name_value("debug", attr)?;
+                check_format_string(&lit)?;
+                options.format = Some(lit.value());
                 continue;
-            } else {
-                t.bounds.push(parse_quote!(std::fmt::Debug));
+            }
+        };
+        for nested in &list.nested {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("skip") => {
+                    options.skip = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("redact") => {
+                    options.redact = Some("<redacted>".to_owned());
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("redact") => {
+                    options.redact = Some(get_lit_str(nv)?.value());
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("redact_with") => {
+                    let path = get_lit_str(nv)?.parse().map_err(|e: syn::Error| e.to_compile_error())?;
+                    options.redact_with = Some(path);
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("with") => {
+                    let path = get_lit_str(nv)?.parse().map_err(|e: syn::Error| e.to_compile_error())?;
+                    options.with = Some(path);
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("bound") => {
+                    options.bound = Some(get_bound(nv)?);
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                    options.rename = Some(get_lit_str(nv)?.value());
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("hex") => {
+                    options.bytes = Some(ByteFormat::Hex);
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("hexdump") => {
+                    options.bytes = Some(ByteFormat::HexDump);
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("base64") => {
+                    options.bytes = Some(ByteFormat::Base64);
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("max_items") => {
+                    options.max_items = Some(get_lit_usize(nv)?);
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("max_len") => {
+                    options.max_len = Some(get_lit_usize(nv)?);
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(nested, "unrecognized debug field attribute").to_compile_error());
+                }
             }
         }
     }
-    let (impl_generics, ty_generics, where_clause) = generics_new.split_for_impl();
-    
-    let builder_fields_fmt = fields.iter().map(|f| {
-        let ident = &f.ident;
-        let literal = ident.as_ref().unwrap().to_string();
 
-        if let Some(attr) = get_attr_debug(f) {
-            let fmt = get_name_value("debug", attr).unwrap();
+    let modes = [
+        options.format.is_some(),
+        options.skip,
+        options.redact.is_some(),
+        options.redact_with.is_some(),
+        options.with.is_some(),
+        options.max_items.is_some(),
+        options.max_len.is_some(),
+        options.bytes.is_some(),
+    ];
+    if modes.iter().filter(|&&set| set).count() > 1 {
+        return Err(syn::Error::new_spanned(field, "at most one of `debug = \"...\"`, `skip`, `redact`, `redact_with`, `with`, `max_items`, `max_len`, `hex`, `hexdump` and `base64` may be used per field").to_compile_error());
+    }
+    Ok(options)
+}
+
+#[derive(Default)]
+struct VariantOptions {
+    // #[debug(rename = "msg")]
+    rename: Option<String>,
+}
+
+fn get_variant_options(variant:&syn::Variant) -> Result<VariantOptions, syn::__private::TokenStream2> {
+    let mut options = VariantOptions::default();
+    for attr in get_attrs_debug(&variant.attrs) {
+        let list = match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => list,
+            _ => return Err(syn::Error::new_spanned(attr, "expected `debug(...)`").to_compile_error()),
+        };
+        for nested in &list.nested {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                    options.rename = Some(get_lit_str(nv)?.value());
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(nested, "unrecognized debug variant attribute").to_compile_error());
+                }
+            }
+        }
+    }
+    Ok(options)
+}
+
+// #[repr(packed)], #[repr(C, packed(2))]
+fn is_packed(attrs:&[syn::Attribute]) -> bool {
+    attrs.iter().filter(|attr| attr.path.is_ident("repr")).any(|attr| match attr.parse_meta() {
+        Ok(syn::Meta::List(list)) => list.nested.iter().any(|nested| match nested {
+            syn::NestedMeta::Meta(meta) => meta.path().is_ident("packed"),
+            _ => false,
+        }),
+        _ => false,
+    })
+}
+
+impl FieldOptions {
+    // skipped, redacted, `with` and byte buffer fields never reach the field's
+    // own Debug impl
+    fn needs_debug(&self) -> bool {
+        !self.skip && self.redact.is_none() && self.redact_with.is_none() && self.with.is_none() && self.bytes.is_none()
+    }
+
+    // fields without a custom format, which every generated impl formats with
+    // its own trait
+    fn is_plain(&self) -> bool {
+        self.needs_debug() && self.format.is_none() && self.max_items.is_none() && self.max_len.is_none()
+    }
+
+    // everything but skipped and placeholder-redacted fields reads the value
+    fn reads_value(&self) -> bool {
+        !self.skip && self.redact.is_none()
+    }
+}
+
+// Collects the type parameters that a field type mentions, skipping anything
+// inside PhantomData<...> and recording associated types like T::Value apart
+// from plain uses of T.
+struct TypeParamVisitor<'a> {
+    params: &'a [syn::Ident],
+    used: Vec<syn::Ident>,
+    projections: Vec<syn::TypePath>,
+}
+
+impl<'a> TypeParamVisitor<'a> {
+    fn is_param(&self, ty:&syn::Type) -> bool {
+        match ty {
+            syn::Type::Path(tp) => tp.qself.is_none() && self.params.iter().any(|p| tp.path.is_ident(p)),
+            _ => false,
+        }
+    }
+}
+
+impl<'a, 'ast> Visit<'ast> for TypeParamVisitor<'a> {
+    fn visit_type_path(&mut self, ty:&'ast syn::TypePath) {
+        if let Some(qself) = &ty.qself {
+            // <T as Trait>::Value
+            if self.is_param(&qself.ty) {
+                self.projections.push(ty.clone());
+                return;
+            }
+        } else if ty.path.leading_colon.is_none() {
+            if let Some(first) = ty.path.segments.first() {
+                if self.params.contains(&first.ident) {
+                    if ty.path.segments.len() == 1 {
+                        self.used.push(first.ident.clone());
+                    } else {
+                        // T::Value
+                        self.projections.push(ty.clone());
+                    }
+                    return;
+                }
+            }
+        }
+        if ty.path.segments.last().is_some_and(|s| s.ident == "PhantomData") {
+            return;
+        }
+        visit::visit_type_path(self, ty);
+    }
+}
+
+// Bounds go on the type parameters and associated types a field mentions,
+// never on whole field types, which would overflow on recursive types like
+// `One<T> { two: Option<Box<Two<T>>> }`.
+fn add_trait_bounds(generics:&mut syn::Generics, tys:&[&syn::Type], bound:&syn::Path) {
+    let params: Vec<syn::Ident> = generics.type_params().map(|t| t.ident.clone()).collect();
+    let mut visitor = TypeParamVisitor { params: &params, used: vec![], projections: vec![] };
+    for ty in tys {
+        visitor.visit_type(ty);
+    }
+
+    let mut bounded = vec![];
+    let where_clause = generics.make_where_clause();
+    for param in params.iter().filter(|p| visitor.used.contains(p)) {
+        where_clause.predicates.push(parse_quote!(#param: #bound));
+    }
+    for projection in visitor.projections {
+        let key = quote!(#projection).to_string();
+        if !bounded.contains(&key) {
+            bounded.push(key);
+            where_clause.predicates.push(parse_quote!(#projection: #bound));
+        }
+    }
+}
+
+// A field with #[debug(bound = "...")] contributes its explicit bound in place
+// of the inferred ones.
+// Plain fields are bounded by the trait being implemented, fields with a
+// custom format by Debug.
+fn add_inferred_bounds(generics:&mut syn::Generics, fields:&[&syn::Field], fmt_trait:&syn::Ident) {
+    let mut plain = vec![];
+    let mut debug = vec![];
+    for field in fields {
+        match get_field_options(field) {
+            Ok(options) => {
+                if let Some(bound) = options.bound {
+                    generics.make_where_clause().predicates.extend(bound);
+                } else if options.is_plain() {
+                    plain.push(&field.ty);
+                } else if options.needs_debug() {
+                    debug.push(&field.ty);
+                }
+            }
+            Err(_) => plain.push(&field.ty),
+        }
+    }
+    if fmt_trait == "Debug" {
+        plain.append(&mut debug);
+    }
+    add_trait_bounds(generics, &plain, &parse_quote!(std::fmt::#fmt_trait));
+    add_trait_bounds(generics, &debug, &parse_quote!(std::fmt::Debug));
+}
+
+// Self::Variant { a: __field0, b: __field1 } / Self::Variant(__field0, __field1)
+fn fields_pattern(path:syn::__private::TokenStream2, fields:&Fields) -> (syn::__private::TokenStream2, Vec<syn::__private::TokenStream2>) {
+    let bindings: Vec<_> = (0..fields.len()).map(|i| {
+        let binding = format_ident!("__field{}", i);
+        quote!(#binding)
+    }).collect();
+    let pattern = match fields {
+        Fields::Named(fs) => {
+            let idents = fs.named.iter().map(|f| &f.ident);
+            quote!(#path { #(#idents: #bindings),* })
+        }
+        Fields::Unnamed(_) => quote!(#path(#(#bindings),*)),
+        Fields::Unit => path,
+    };
+    (pattern, bindings)
+}
+
+// Debug wrapper around a `fn(&T, &mut fmt::Formatter) -> fmt::Result`
+fn debug_with(binding:&syn::__private::TokenStream2, with:syn::__private::TokenStream2) -> syn::__private::TokenStream2 {
+    quote!(&{
+        struct __DebugWith<'__a, T: ?Sized>(&'__a T, fn(&T, &mut std::fmt::Formatter) -> std::fmt::Result);
+        impl<'__a, T: ?Sized> std::fmt::Debug for __DebugWith<'__a, T> {
+            fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+                (self.1)(self.0, fmt)
+            }
+        }
+        __DebugWith(#binding, #with)
+    })
+}
+
+fn field_value(options:&FieldOptions, binding:&syn::__private::TokenStream2, fmt_trait:&syn::Ident) -> syn::__private::TokenStream2 {
+    if let Some(fmt) = &options.format {
+        quote!(&format_args!(#fmt, #binding))
+    } else if let Some(placeholder) = &options.redact {
+        quote!(&format_args!("{}", #placeholder))
+    } else if let Some(mask) = &options.redact_with {
+        quote!(&format_args!("{}", #mask(#binding)))
+    } else if let Some(with) = &options.with {
+        debug_with(binding, quote!(#with))
+    } else if let Some(max) = options.max_items {
+        quote!(&::derive_debug_support::MaxItems::new(#binding, #max))
+    } else if let Some(max) = options.max_len {
+        quote!(&::derive_debug_support::MaxLen::new(#binding, #max))
+    } else if let Some(bytes) = options.bytes {
+        let wrapper = match bytes {
+            ByteFormat::Hex => quote!(Hex),
+            ByteFormat::HexDump => quote!(HexDump),
+            ByteFormat::Base64 => quote!(Base64),
+        };
+        quote!(&::derive_debug_support::#wrapper(#binding))
+    } else if fmt_trait != "Debug" {
+        debug_with(binding, quote!(std::fmt::#fmt_trait::fmt))
+    } else {
+        // passed through as is so that {:#?} reaches nested values
+        quote!(#binding)
+    }
+}
+
+// &self.a, &self.b / &self.0, &self.1
+fn struct_bindings(fields:&Fields) -> Vec<syn::__private::TokenStream2> {
+    fields.iter().enumerate().map(|(i, f)| {
+        let member = match &f.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(i.into()),
+        };
+        quote!(&self.#member)
+    }).collect()
+}
+
+// Fields of a #[repr(packed)] struct may be unaligned and cannot be borrowed,
+// so the ones that get formatted are copied into locals first. Non-Copy fields
+// fail the assertion, spanned at the field's type.
+fn packed_bindings(fields:&Fields) -> (syn::__private::TokenStream2, Vec<syn::__private::TokenStream2>) {
+    let mut copies = vec![];
+    let bindings = fields.iter().zip(struct_bindings(fields)).enumerate().map(|(i, (f, binding))| {
+        if !get_field_options(f).map_or(true, |options| options.reads_value()) {
+            return binding;
+        }
+        let local = format_ident!("__field{}", i);
+        let member = match &f.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(i.into()),
+        };
+        let ty = &f.ty;
+        copies.push(quote_spanned!(ty.span()=>
+            __packed_field_must_be_copy::<#ty>();
+        ));
+        copies.push(quote!(let #local = self.#member;));
+        quote!(&#local)
+    }).collect();
+    let prelude = quote!(
+        fn __packed_field_must_be_copy<T: std::marker::Copy>() {}
+        #(#copies)*
+    );
+    (prelude, bindings)
+}
+
+fn variant_literal(v:&syn::Variant, rename_all:Option<RenameRule>) -> Result<String, syn::__private::TokenStream2> {
+    let name = v.ident.unraw().to_string();
+    Ok(match (get_variant_options(v)?.rename, rename_all) {
+        (Some(rename), _) => rename,
+        (None, Some(rule)) => rule.apply_to_variant(&name),
+        (None, None) => name,
+    })
+}
+
+// the name a named field is shown under, None for tuple fields
+fn field_literal(f:&syn::Field, options:&FieldOptions, rename_all:Option<RenameRule>) -> Result<Option<String>, syn::__private::TokenStream2> {
+    if f.ident.is_none() && options.rename.is_some() {
+        return Err(syn::Error::new_spanned(f, "#[debug(rename = \"...\")] requires a named field").to_compile_error());
+    }
+    Ok(f.ident.as_ref().map(|ident| {
+        let name = ident.unraw().to_string();
+        match (options.rename.clone(), rename_all) {
+            (Some(rename), _) => rename,
+            (None, Some(rule)) => rule.apply_to_field(&name),
+            (None, None) => name,
+        }
+    }))
+}
+
+// `bindings[i]` evaluates to a reference to the i-th field
+fn fields_fmt(literal:&str, fields:&Fields, bindings:&[syn::__private::TokenStream2], rename_all:Option<RenameRule>, fmt_trait:&syn::Ident) -> Result<syn::__private::TokenStream2, syn::__private::TokenStream2> {
+    let mut field_literals = vec![];
+    let mut values = vec![];
+    let mut skipped = false;
+    for (f, binding) in fields.iter().zip(bindings) {
+        let options = get_field_options(f)?;
+        if options.skip {
+            skipped = true;
+            continue;
+        }
+        field_literals.push(field_literal(f, &options, rename_all)?);
+        values.push(field_value(&options, binding, fmt_trait));
+    }
+    let finish = if skipped {
+        quote!(finish_non_exhaustive)
+    } else {
+        quote!(finish)
+    };
+
+    Ok(match fields {
+        Fields::Named(_) => {
+            let field_literals = field_literals.iter().flatten();
             quote!(
-                .field(#literal, &format_args!(#fmt, &self.#ident))
+                fmt.debug_struct(#literal)
+                    #(.field(#field_literals, #values))*
+                    .#finish()
             )
-        } else {
+        }
+        Fields::Unnamed(_) => quote!(
+            fmt.debug_tuple(#literal)
+                #(.field(#values))*
+                .#finish()
+        ),
+        Fields::Unit => quote!(
+            fmt.write_str(#literal)
+        ),
+    })
+}
+
+fn fmt_body(ast:&syn::DeriveInput, container_options:&ContainerOptions, packed:bool, deriver_literal:&str, fmt_trait:&syn::Ident) -> Result<syn::__private::TokenStream2, syn::__private::TokenStream2> {
+    Ok(match &ast.data {
+        syn::Data::Struct(ds) if container_options.transparent => {
+            let field = match ds.fields.iter().next() {
+                Some(field) if ds.fields.len() == 1 => field,
+                _ => {
+                    return Err(syn::Error::new_spanned(&ast.ident, "#[debug(transparent)] requires a struct with exactly one field").to_compile_error());
+                }
+            };
+            let options = get_field_options(field)?;
+            if options.skip {
+                return Err(syn::Error::new_spanned(field, "the field of a #[debug(transparent)] struct cannot be skipped").to_compile_error());
+            }
+            let (prelude, bindings) = if packed {
+                packed_bindings(&ds.fields)
+            } else {
+                (quote!(), struct_bindings(&ds.fields))
+            };
+            let value = field_value(&options, &bindings[0], fmt_trait);
             quote!(
-                .field(#literal, &format_args!("{:?}", &self.#ident))
+                #prelude
+                std::fmt::Debug::fmt(#value, fmt)
             )
         }
-    });
-
-    let output = quote! (
-        impl #impl_generics std::fmt::Debug for #deriver_ident #ty_generics #where_clause {
-            fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        _ if container_options.transparent => {
+            return Err(syn::Error::new_spanned(&ast.ident, "#[debug(transparent)] requires a struct with exactly one field").to_compile_error());
+        }
+        syn::Data::Struct(ds) => {
+            let (prelude, bindings) = if packed {
+                packed_bindings(&ds.fields)
+            } else {
+                (quote!(), struct_bindings(&ds.fields))
+            };
+            let fmt = fields_fmt(deriver_literal, &ds.fields, &bindings, container_options.rename_all, fmt_trait)?;
+            quote!(
+                #prelude
+                #fmt
+            )
+        }
+        syn::Data::Enum(_) if container_options.name.is_some() => {
+            return Err(syn::Error::new_spanned(&ast.ident, "#[debug(name = \"...\")] has no effect on enums, use #[debug(rename = \"...\")] on the variants").to_compile_error());
+        }
+        syn::Data::Enum(de) if de.variants.is_empty() => quote!(match *self {}),
+        syn::Data::Enum(de) => {
+            let arms: Result<Vec<_>, syn::__private::TokenStream2> = de.variants.iter().map(|v| {
+                let variant_ident = &v.ident;
+                let variant_literal = variant_literal(v, container_options.rename_all)?;
+                let (pattern, bindings) = fields_pattern(quote!(Self::#variant_ident), &v.fields);
+                let fmt = fields_fmt(&variant_literal, &v.fields, &bindings, None, fmt_trait)?;
+                Ok(quote!(#pattern => #fmt,))
+            }).collect();
+            let arms = arms?;
+            quote!(
+                match self {
+                    #(#arms)*
+                }
+            )
+        }
+        // Reading the bytes is only sound if all of them are initialized,
+        // which the caller vouches for by opting in with
+        // union_as = "unsafe_bytes". A field smaller than the union would
+        // leave bytes uninitialized whenever it is the active one, so that is
+        // rejected at compile time. That only catches size mismatches: padding
+        // inside a same-size field, like the byte after `u8` in `(u8, u16)`,
+        // stays the caller's responsibility.
+        syn::Data::Union(du) => {
+            let size_checks = du.fields.named.iter().map(|f| {
+                let ty = &f.ty;
+                let message = format!("field `{}` is smaller than the union, union_as = \"unsafe_bytes\" would read uninitialized bytes", f.ident.as_ref().unwrap());
+                quote_spanned!(ty.span()=>
+                    const { assert!(std::mem::size_of::<#ty>() == std::mem::size_of::<Self>(), #message) };
+                )
+            });
+            quote!(
+                #(#size_checks)*
+                let bytes = unsafe {
+                    std::slice::from_raw_parts(self as *const Self as *const u8, std::mem::size_of::<Self>())
+                };
                 fmt.debug_struct(#deriver_literal)
-                    #(#builder_fields_fmt)*
-                   .finish()
+                    .field("bytes", &bytes)
+                    .finish()
+            )
+        }
+    })
+}
+
+// One visit_field call per shown field, with the same name and value that
+// Debug shows for it. Tuple fields are named by their index.
+fn fields_visit(fields:&Fields, bindings:&[syn::__private::TokenStream2], rename_all:Option<RenameRule>) -> Result<Vec<syn::__private::TokenStream2>, syn::__private::TokenStream2> {
+    let debug = format_ident!("Debug");
+    let mut calls = vec![];
+    for (i, (f, binding)) in fields.iter().zip(bindings).enumerate() {
+        let options = get_field_options(f)?;
+        if options.skip {
+            continue;
+        }
+        let name = field_literal(f, &options, rename_all)?.unwrap_or_else(|| i.to_string());
+        let value = field_value(&options, binding, &debug);
+        calls.push(quote!(visitor.visit_field(#name, #value);));
+    }
+    Ok(calls)
+}
+
+fn visit_body(ast:&syn::DeriveInput, container_options:&ContainerOptions, packed:bool) -> Result<syn::__private::TokenStream2, syn::__private::TokenStream2> {
+    Ok(match &ast.data {
+        syn::Data::Struct(ds) => {
+            let (prelude, bindings) = if packed {
+                packed_bindings(&ds.fields)
+            } else {
+                (quote!(), struct_bindings(&ds.fields))
+            };
+            let calls = fields_visit(&ds.fields, &bindings, container_options.rename_all)?;
+            quote!(
+                #prelude
+                #(#calls)*
+            )
+        }
+        syn::Data::Enum(de) if de.variants.is_empty() => quote!(match *self {}),
+        syn::Data::Enum(de) => {
+            let mut arms = vec![];
+            for v in &de.variants {
+                let variant_ident = &v.ident;
+                let (pattern, bindings) = fields_pattern(quote!(Self::#variant_ident), &v.fields);
+                let variant_literal = variant_literal(v, container_options.rename_all)?;
+                let calls = fields_visit(&v.fields, &bindings, None)?;
+                arms.push(quote!(#pattern => {
+                    visitor.visit_variant(#variant_literal);
+                    #(#calls)*
+                }));
             }
+            quote!(
+                match self {
+                    #(#arms)*
+                }
+            )
         }
-    );
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new_spanned(&ast.ident, "#[debug(visit_fields)] is not supported on unions").to_compile_error());
+        }
+    })
+}
+
+// DERIVE_DEBUG_TRACE=Field,Other traces those types, DERIVE_DEBUG_TRACE=* all
+fn traced_by_env(ident:&syn::Ident) -> bool {
+    match std::env::var("DERIVE_DEBUG_TRACE") {
+        Ok(names) => names.split(',').map(str::trim).any(|name| name == "*" || ident == name),
+        Err(_) => false,
+    }
+}
+
+#[proc_macro_derive(CustomDebug, attributes(debug))]
+pub fn derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(input as syn::DeriveInput);
+    let deriver_ident = &ast.ident;
+
+    let container_options = match get_container_options(&ast.attrs) {
+        Ok(options) => options,
+        Err(err) => return err.into(),
+    };
 
-    // when an error occurs, `str` used to show macro expansion 
-    // let str = output.to_string();
+    let fields: Vec<&syn::Field> = match &ast.data {
+        syn::Data::Struct(_) | syn::Data::Enum(_) if container_options.union_as_bytes => {
+            return syn::Error::new_spanned(&ast.ident, "#[debug(union_as = \"unsafe_bytes\")] only applies to unions").to_compile_error().into();
+        }
+        syn::Data::Struct(ds) => ds.fields.iter().collect(),
+        syn::Data::Enum(de) => de.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+        // only the raw bytes are shown, so no field needs a bound
+        syn::Data::Union(_) if !container_options.also.is_empty() => {
+            return syn::Error::new_spanned(&ast.ident, "#[debug(also = \"...\")] is not supported on unions").to_compile_error().into();
+        }
+        syn::Data::Union(_) if container_options.union_as_bytes => vec![],
+        syn::Data::Union(_) => {
+            return syn::Error::new_spanned(&ast.ident, "derive(CustomDebug) on a union requires #[debug(union_as = \"unsafe_bytes\")]").to_compile_error().into();
+        }
+    };
+    let packed = is_packed(&ast.attrs);
+
+    // #[debug(name = "Msg")]
+    let deriver_literal = container_options.name.clone().unwrap_or_else(|| deriver_ident.unraw().to_string());
+
+    // #[debug(bound = "...")] on the type replaces every inferred bound
+    let generics_for = |fmt_trait:&syn::Ident| {
+        let mut generics_new = ast.generics.clone();
+        if let Some(bound) = &container_options.bound {
+            generics_new.make_where_clause().predicates.extend(bound.clone());
+        } else {
+            add_inferred_bounds(&mut generics_new, &fields, fmt_trait);
+        }
+        if packed {
+            let copied: Vec<_> = fields.iter()
+                .filter(|f| get_field_options(f).map_or(true, |options| options.reads_value()))
+                .map(|f| &f.ty)
+                .collect();
+            add_trait_bounds(&mut generics_new, &copied, &parse_quote!(std::marker::Copy));
+        }
+        generics_new
+    };
+
+    let debug = format_ident!("Debug");
+    let mut output = quote!();
+    for fmt_trait in std::iter::once(&debug).chain(&container_options.also) {
+        let generics_new = generics_for(fmt_trait);
+        let (impl_generics, ty_generics, where_clause) = generics_new.split_for_impl();
+
+        let body = match fmt_body(&ast, &container_options, packed, &deriver_literal, fmt_trait) {
+            Ok(body) => body,
+            Err(err) => return err.into(),
+        };
+
+        // #[debug(compact)]: formatting `self` again through write! starts from
+        // a fresh Formatter, which drops the alternate flag for everything nested.
+        // Only Debug reads `#` as pretty-printing; for the numeric traits it asks
+        // for a 0x/0b prefix and is left alone.
+        let compact = if container_options.compact && *fmt_trait == debug {
+            quote!(
+                if fmt.alternate() {
+                    return write!(fmt, "{:?}", self);
+                }
+            )
+        } else {
+            quote!()
+        };
 
-    // let res = quote!(
-    //     fn test () {
-    //         let str = #str;
-    //     }
-    // );
+        output.extend(quote! (
+            impl #impl_generics std::fmt::#fmt_trait for #deriver_ident #ty_generics #where_clause {
+                fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    #compact
+                    #body
+                }
+            }
+        ));
+    }
+
+    // #[debug(visit_fields)]
+    if container_options.visit_fields {
+        let generics_new = generics_for(&debug);
+        let (impl_generics, ty_generics, where_clause) = generics_new.split_for_impl();
+        let body = match visit_body(&ast, &container_options, packed) {
+            Ok(body) => body,
+            Err(err) => return err.into(),
+        };
+        output.extend(quote!(
+            impl #impl_generics ::derive_debug_support::VisitFields for #deriver_ident #ty_generics #where_clause {
+                fn visit(&self, visitor: &mut dyn ::derive_debug_support::FieldVisitor) {
+                    #body
+                }
+            }
+        ));
+    }
+
+    if container_options.trace || traced_by_env(deriver_ident) {
+        eprintln!("derive(CustomDebug) for {}:\n{}", deriver_ident, output);
+    }
 
     TokenStream::from(output)
 }
+
+#[proc_macro_derive(CustomDisplay, attributes(display))]
+pub fn derive_display(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(input as syn::DeriveInput);
+    match display::expand(&ast) {
+        Ok(output) => output.into(),
+        Err(err) => err.into(),
+    }
+}
@@ -0,0 +1,204 @@
+// Runtime helpers for the code generated by derive_debug.
+//
+// A proc-macro crate can only export macros, so the wrapper types that the
+// generated Debug impls format fields through live in this crate instead.
+// Crates using the attributes that need them depend on both.
+
+use std::fmt::{self, Debug};
+
+/// Formats at most `max` items of a collection, followed by a count of the
+/// items left out.
+///
+/// Generated for `#[debug(max_items = N)]`.
+pub struct MaxItems<'a, C: ?Sized> {
+    collection: &'a C,
+    max: usize,
+}
+
+impl<'a, C: ?Sized> MaxItems<'a, C> {
+    pub fn new(collection: &'a C, max: usize) -> Self {
+        MaxItems { collection, max }
+    }
+}
+
+impl<'a, C: ?Sized> Debug for MaxItems<'a, C>
+where
+    &'a C: IntoIterator,
+    <&'a C as IntoIterator>::Item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut items = self.collection.into_iter();
+        let mut list = f.debug_list();
+        list.entries(items.by_ref().take(self.max));
+        let rest = items.count();
+        if rest > 0 {
+            list.entry(&format_args!("... ({} more)", rest));
+        }
+        list.finish()
+    }
+}
+
+/// Formats at most `max` characters of a string or `max` bytes of a byte
+/// buffer, followed by a count of what was left out.
+///
+/// Generated for `#[debug(max_len = N)]`.
+pub struct MaxLen<'a, T: ?Sized> {
+    value: &'a T,
+    max: usize,
+}
+
+impl<'a, T: ?Sized> MaxLen<'a, T> {
+    pub fn new(value: &'a T, max: usize) -> Self {
+        MaxLen { value, max }
+    }
+}
+
+impl<'a, T: ?Sized + Truncate> Debug for MaxLen<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt_truncated(self.max, f)
+    }
+}
+
+/// Strings and byte buffers that [`MaxLen`] knows how to shorten.
+pub trait Truncate {
+    fn fmt_truncated(&self, max: usize, f: &mut fmt::Formatter) -> fmt::Result;
+}
+
+impl Truncate for str {
+    fn fmt_truncated(&self, max: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.char_indices().nth(max) {
+            Some((end, _)) => {
+                Debug::fmt(&self[..end], f)?;
+                write!(f, "... ({} more)", self[end..].chars().count())
+            }
+            None => Debug::fmt(self, f),
+        }
+    }
+}
+
+impl Truncate for String {
+    fn fmt_truncated(&self, max: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_str().fmt_truncated(max, f)
+    }
+}
+
+impl Truncate for [u8] {
+    fn fmt_truncated(&self, max: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        MaxItems::new(self, max).fmt(f)
+    }
+}
+
+impl Truncate for Vec<u8> {
+    fn fmt_truncated(&self, max: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_slice().fmt_truncated(max, f)
+    }
+}
+
+impl<const N: usize> Truncate for [u8; N] {
+    fn fmt_truncated(&self, max: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_slice().fmt_truncated(max, f)
+    }
+}
+
+impl<T: ?Sized + Truncate> Truncate for &T {
+    fn fmt_truncated(&self, max: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt_truncated(max, f)
+    }
+}
+
+/// Formats bytes as contiguous lowercase hex, like `deadbeef`.
+///
+/// Generated for `#[debug(hex)]`.
+pub struct Hex<'a, T: ?Sized>(pub &'a T);
+
+impl<'a, T: ?Sized + AsRef<[u8]>> Debug for Hex<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in self.0.as_ref() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats bytes as a multi-line dump with an offset column, sixteen hex
+/// bytes per line and an ASCII column:
+///
+/// ```text
+/// 00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a        |Hello, world!.|
+/// ```
+///
+/// Generated for `#[debug(hexdump)]`.
+pub struct HexDump<'a, T: ?Sized>(pub &'a T);
+
+impl<'a, T: ?Sized + AsRef<[u8]>> Debug for HexDump<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bytes = self.0.as_ref();
+        if bytes.is_empty() {
+            return f.write_str("[]");
+        }
+        for (line, chunk) in bytes.chunks(16).enumerate() {
+            if line > 0 {
+                f.write_str("\n")?;
+            }
+            write!(f, "{:08x} ", line * 16)?;
+            for i in 0..16 {
+                if i == 8 {
+                    f.write_str(" ")?;
+                }
+                match chunk.get(i) {
+                    Some(byte) => write!(f, " {:02x}", byte)?,
+                    None => f.write_str("   ")?,
+                }
+            }
+            f.write_str("  |")?;
+            for &byte in chunk {
+                let c = if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' };
+                write!(f, "{}", c)?;
+            }
+            f.write_str("|")?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats bytes as standard padded base64.
+///
+/// Generated for `#[debug(base64)]`.
+pub struct Base64<'a, T: ?Sized>(pub &'a T);
+
+impl<'a, T: ?Sized + AsRef<[u8]>> Debug for Base64<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        for chunk in self.0.as_ref().chunks(3) {
+            let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    let sextet = (n >> (18 - 6 * i)) & 0x3f;
+                    write!(f, "{}", ALPHABET[sextet as usize] as char)?;
+                } else {
+                    f.write_str("=")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Receives the fields of a value one at a time, for loggers that record
+/// key/value pairs rather than a single formatted string.
+///
+/// For an enum, `visit_variant` is called first with the name of the active
+/// variant, as Debug shows it, followed by that variant's fields. Visitors
+/// that only see structs can leave it out.
+pub trait FieldVisitor {
+    fn visit_variant(&mut self, _name: &str) {}
+    fn visit_field(&mut self, name: &str, value: &dyn Debug);
+}
+
+/// Walks the fields that the derived Debug impl shows, with the same names
+/// and the same formatting, so that structured logs and Debug output agree.
+///
+/// Generated for `#[debug(visit_fields)]`.
+pub trait VisitFields {
+    fn visit(&self, visitor: &mut dyn FieldVisitor);
+}
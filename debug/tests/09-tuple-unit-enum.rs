@@ -0,0 +1,48 @@
+// Tuple structs, unit structs and enums get the same treatment as structs with
+// named fields. Tuple-like shapes are printed with debug_tuple, unit-like ones
+// as the bare name, and an enum matches on its variants and picks the
+// Formatter helper for each variant's shape.
+//
+// The #[debug = "..."] field attribute works inside tuple structs and enum
+// variants too.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Pair(&'static str, #[debug = "0b{:08b}"] u8);
+
+#[derive(CustomDebug)]
+pub struct Marker;
+
+#[derive(CustomDebug)]
+pub enum Shape<T> {
+    Empty,
+    Circle(T),
+    Rect {
+        width: T,
+        #[debug = "{:#x}"]
+        height: u32,
+    },
+}
+
+#[derive(CustomDebug)]
+pub enum Never {}
+
+fn main() {
+    assert_eq!(format!("{:?}", Pair("F", 0b00011100)), r#"Pair("F", 0b00011100)"#);
+    assert_eq!(format!("{:?}", Marker), "Marker");
+
+    assert_eq!(format!("{:?}", Shape::<u8>::Empty), "Empty");
+    assert_eq!(format!("{:?}", Shape::Circle(1)), "Circle(1)");
+    assert_eq!(
+        format!("{:?}", Shape::Rect { width: 2, height: 255 }),
+        "Rect { width: 2, height: 0xff }",
+    );
+    assert_eq!(
+        format!("{:#?}", Shape::Circle(1)),
+        "Circle(\n    1,\n)",
+    );
+
+    fn assert_debug<F: std::fmt::Debug>() {}
+    assert_debug::<Never>();
+}
@@ -0,0 +1,60 @@
+// Secrets must never end up in logs. A field marked #[debug(skip)] is left out
+// entirely and the output ends with `..` via finish_non_exhaustive, so a
+// reader can tell something was omitted. A field marked #[debug(redact)] is
+// shown with a placeholder in place of its value; the placeholder defaults to
+// <redacted> and can be chosen with #[debug(redact = "...")].
+//
+// For partial masking, #[debug(redact_with = "path")] calls the given function
+// with a reference to the field and displays whatever it returns:
+//
+//     fn mask(value: &T) -> impl Display
+
+use derive_debug::CustomDebug;
+
+fn last_four(value: &str) -> String {
+    let shown = value.len().saturating_sub(4);
+    format!("{}{}", "*".repeat(shown), &value[shown..])
+}
+
+#[derive(CustomDebug)]
+pub struct Credentials {
+    user: &'static str,
+    #[debug(skip)]
+    token: &'static str,
+    #[debug(redact)]
+    password: &'static str,
+    #[debug(redact = "***")]
+    pin: u16,
+    #[debug(redact_with = "last_four")]
+    card: &'static str,
+}
+
+#[derive(CustomDebug)]
+pub enum Auth {
+    Token(#[debug(redact)] &'static str),
+    Basic {
+        user: &'static str,
+        #[debug(skip)]
+        password: &'static str,
+    },
+}
+
+fn main() {
+    let credentials = Credentials {
+        user: "root",
+        token: "abcdef",
+        password: "hunter2",
+        pin: 1234,
+        card: "4111111111111111",
+    };
+    assert_eq!(
+        format!("{:?}", credentials),
+        r#"Credentials { user: "root", password: <redacted>, pin: ***, card: ************1111, .. }"#,
+    );
+
+    assert_eq!(format!("{:?}", Auth::Token("abcdef")), "Token(<redacted>)");
+    assert_eq!(
+        format!("{:?}", Auth::Basic { user: "root", password: "hunter2" }),
+        r#"Basic { user: "root", .. }"#,
+    );
+}
@@ -0,0 +1,57 @@
+// A format string is not enough for types that have no suitable Debug impl of
+// their own, such as a raw byte buffer that should read as hex. The field
+// attribute #[debug(with = "path::to_fn")] hands the field to a function with
+// the same shape as Debug::fmt:
+//
+//     fn(&T, &mut fmt::Formatter) -> fmt::Result
+//
+// The function sees the caller's Formatter, so flags like {:#?} reach it.
+
+use derive_debug::CustomDebug;
+use std::fmt;
+
+mod hex {
+    use std::fmt;
+
+    pub fn bytes(value: &Vec<u8>, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in value {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+fn alternate(value: &u8, f: &mut fmt::Formatter) -> fmt::Result {
+    if f.alternate() {
+        write!(f, "alt {}", value)
+    } else {
+        write!(f, "{}", value)
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Packet {
+    #[debug(with = "hex::bytes")]
+    payload: Vec<u8>,
+    #[debug(with = "alternate")]
+    kind: u8,
+}
+
+#[derive(CustomDebug)]
+pub enum Frame {
+    Data(#[debug(with = "hex::bytes")] Vec<u8>),
+}
+
+fn main() {
+    let packet = Packet {
+        payload: vec![0xde, 0xad, 0xbe, 0xef],
+        kind: 7,
+    };
+    assert_eq!(format!("{:?}", packet), "Packet { payload: deadbeef, kind: 7 }");
+    assert_eq!(
+        format!("{:#?}", packet),
+        "Packet {\n    payload: deadbeef,\n    kind: alt 7,\n}",
+    );
+
+    assert_eq!(format!("{:?}", Frame::Data(vec![1, 2])), "Data(0102)");
+}
@@ -0,0 +1,57 @@
+// Bound inference looks through the whole field type, not only its outermost
+// path. A type parameter that appears anywhere outside PhantomData gets a
+// `T: Debug` bound, an associated type projection gets a bound of its own,
+// and fields that never reach their own Debug impl (skipped, redacted, or
+// printed through a `with` function) don't contribute bounds at all.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub struct Node<T> {
+    value: Rc<T>,
+    children: Vec<Box<Node<T>>>,
+}
+
+#[derive(CustomDebug)]
+pub struct Qualified<T: Trait, M> {
+    value: <T as Trait>::Value,
+    values: Option<Vec<T::Value>>,
+    marker: Rc<PhantomData<M>>,
+}
+
+#[derive(CustomDebug)]
+pub struct Holder<T, S> {
+    value: T,
+    #[debug(skip)]
+    secret: S,
+}
+
+#[derive(CustomDebug)]
+pub enum Either<L, R> {
+    Left(Box<L>),
+    Right(#[debug(redact)] R),
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+    }
+
+    assert_debug::<Node<u8>>();
+    assert_debug::<Qualified<Id, NotDebug>>();
+    assert_debug::<Holder<u8, NotDebug>>();
+    assert_debug::<Either<u8, NotDebug>>();
+}
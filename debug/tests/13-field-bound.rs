@@ -0,0 +1,52 @@
+// #[debug(bound = "...")] also works on a single field. There it replaces only
+// the bounds that would have been inferred from that field, while the other
+// fields keep contributing their inferred bounds. An empty string opts the
+// field out of bounds entirely.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug, Display};
+
+fn display<T: Display>(value: &T, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", value)
+}
+
+#[derive(CustomDebug)]
+pub struct Labeled<L, T> {
+    #[debug(with = "display", bound = "L: Display")]
+    label: L,
+    value: T,
+}
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub struct Wrapper<T: Trait, U> {
+    #[debug(bound = "T::Value: Debug")]
+    inner: Inner<T>,
+    #[debug(bound = "")]
+    marker: std::marker::PhantomData<U>,
+}
+
+#[derive(CustomDebug)]
+pub struct Inner<T: Trait> {
+    values: Vec<T::Value>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+    }
+
+    assert_debug::<Wrapper<Id, NotDebug>>();
+
+    let labeled = Labeled { label: "answer", value: 42 };
+    assert_eq!(format!("{:?}", labeled), "Labeled { label: answer, value: 42 }");
+}
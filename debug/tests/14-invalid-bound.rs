@@ -0,0 +1,18 @@
+// A bound that does not parse as where-predicates is reported at the string
+// literal, whether it is on the type or on a field.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(bound = "T Debug")]
+pub struct Field<T> {
+    value: T,
+}
+
+#[derive(CustomDebug)]
+pub struct Other<T> {
+    #[debug(bound = "T: +")]
+    value: T,
+}
+
+fn main() {}
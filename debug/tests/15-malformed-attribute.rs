@@ -0,0 +1,45 @@
+// Malformed #[debug] attributes are reported at the attribute instead of
+// panicking inside the macro. The format string of #[debug = "..."] is checked
+// at expansion time too: it is passed to format_args! with the field as its
+// only argument, so it needs at least one placeholder and every placeholder
+// must refer to that argument.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct NotAString {
+    #[debug = 5]
+    value: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct NotAMeta {
+    #[debug(123)]
+    value: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct TwoArguments {
+    #[debug = "{} and {}"]
+    value: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct NamedArgument {
+    #[debug = "{value:?}"]
+    value: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct NoPlaceholder {
+    #[debug = "constant"]
+    value: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Unbalanced {
+    #[debug = "{:?"]
+    value: u8,
+}
+
+fn main() {}
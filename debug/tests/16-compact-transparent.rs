@@ -0,0 +1,64 @@
+// Fields without an attribute are formatted with the caller's Formatter, so
+// {:#?} pretty-prints nested values as well as the outer struct.
+//
+// #[debug(compact)] on the type keeps its output on one line, nested values
+// included, even when it is printed with {:#?}.
+//
+// #[debug(transparent)] on a struct with exactly one field prints only that
+// field, the way a #[repr(transparent)] newtype should look in logs. The
+// field's own #[debug] attributes still apply.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(CustomDebug)]
+pub struct Line {
+    from: Point,
+    to: Point,
+}
+
+#[derive(CustomDebug)]
+#[debug(compact)]
+pub struct CompactLine {
+    from: Point,
+    to: Point,
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Meters(f64);
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Mask {
+    #[debug = "0b{:08b}"]
+    bits: u8,
+}
+
+fn main() {
+    let line = Line {
+        from: Point { x: 0, y: 0 },
+        to: Point { x: 1, y: 2 },
+    };
+    assert_eq!(
+        format!("{:#?}", line),
+        "Line {\n    from: Point {\n        x: 0,\n        y: 0,\n    },\n    to: Point {\n        x: 1,\n        y: 2,\n    },\n}",
+    );
+
+    let line = CompactLine {
+        from: Point { x: 0, y: 0 },
+        to: Point { x: 1, y: 2 },
+    };
+    let expected = "CompactLine { from: Point { x: 0, y: 0 }, to: Point { x: 1, y: 2 } }";
+    assert_eq!(format!("{:?}", line), expected);
+    assert_eq!(format!("{:#?}", line), expected);
+
+    assert_eq!(format!("{:?}", Meters(1.5)), "1.5");
+    assert_eq!(format!("{:?}", vec![Meters(1.0)]), "[1.0]");
+    assert_eq!(format!("{:?}", Mask { bits: 5 }), "0b00000101");
+}
@@ -0,0 +1,80 @@
+// Names in the output can differ from the names in the source, for structs
+// that mirror a wire protocol whose operators expect the protocol's names.
+//
+//   - #[debug(name = "...")] on a struct replaces the type name;
+//   - #[debug(rename = "...")] on a field or variant replaces its name;
+//   - #[debug(rename_all = "...")] on the type renames every field of a struct
+//     or every variant of an enum, following serde's case conventions.
+//
+// An explicit rename wins over rename_all. Identifiers need not be ASCII, and
+// their case is changed by Unicode rules.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(name = "Msg", rename_all = "camelCase")]
+pub struct Message {
+    msg_type: u8,
+    sequence_number: u32,
+    #[debug(rename = "TTL")]
+    time_to_live: u8,
+    r#type: &'static str,
+}
+
+#[derive(CustomDebug)]
+#[debug(rename_all = "SCREAMING-KEBAB-CASE")]
+pub struct Header {
+    content_length: usize,
+}
+
+#[derive(CustomDebug)]
+#[debug(rename_all = "snake_case")]
+pub enum Command {
+    KeepAlive,
+    SetTimeout(u32),
+    #[debug(rename = "hello")]
+    Handshake {
+        #[debug(rename = "protocolVersion")]
+        protocol_version: u8,
+    },
+}
+
+#[derive(CustomDebug)]
+#[debug(rename_all = "camelCase")]
+pub enum Wood {
+    Ébène,
+}
+
+#[derive(CustomDebug)]
+#[debug(rename_all = "snake_case")]
+pub enum Lang {
+    FrançaisÉcrit,
+}
+
+fn main() {
+    let message = Message {
+        msg_type: 1,
+        sequence_number: 7,
+        time_to_live: 64,
+        r#type: "ping",
+    };
+    assert_eq!(
+        format!("{:?}", message),
+        r#"Msg { msgType: 1, sequenceNumber: 7, TTL: 64, type: "ping" }"#,
+    );
+
+    assert_eq!(
+        format!("{:?}", Header { content_length: 5 }),
+        "Header { CONTENT-LENGTH: 5 }",
+    );
+
+    assert_eq!(format!("{:?}", Command::KeepAlive), "keep_alive");
+    assert_eq!(format!("{:?}", Command::SetTimeout(30)), "set_timeout(30)");
+    assert_eq!(
+        format!("{:?}", Command::Handshake { protocol_version: 2 }),
+        "hello { protocolVersion: 2 }",
+    );
+
+    assert_eq!(format!("{:?}", Wood::Ébène), "ébène");
+    assert_eq!(format!("{:?}", Lang::FrançaisÉcrit), "français_écrit");
+}
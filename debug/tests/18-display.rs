@@ -0,0 +1,57 @@
+// derive(CustomDisplay) comes from the same crate and shares the format string
+// handling and bound inference of derive(CustomDebug). A struct takes a
+// template on the type that interpolates its fields by name, or by position
+// for tuple structs, with the usual format specs:
+//
+//     #[display("{name} ({id:#x})")]
+//
+// Enums take a template on each variant. Unit variants without one display
+// their name.
+//
+// Type parameters are bounded by the trait their placeholder formats with,
+// so `{value}` requires Display while `{value:?}` requires Debug.
+
+use derive_debug::CustomDisplay;
+use std::fmt::Display;
+
+#[derive(CustomDisplay)]
+#[display("{name} ({id:#x})")]
+pub struct User {
+    name: &'static str,
+    id: u32,
+}
+
+#[derive(CustomDisplay)]
+#[display("{0}..{1} {{step {2}}}")]
+pub struct Range(i32, i32, u8);
+
+#[derive(CustomDisplay)]
+pub enum Event<T> {
+    Started,
+    #[display("stopped after {0}s")]
+    Stopped(u64),
+    #[display("value {value:?} from {source}")]
+    Value { value: T, source: &'static str },
+}
+
+fn assert_display<F: Display>() {}
+
+fn main() {
+    let user = User { name: "root", id: 255 };
+    assert_eq!(user.to_string(), "root (0xff)");
+
+    assert_eq!(Range(1, 5, 2).to_string(), "1..5 {step 2}");
+
+    assert_eq!(Event::<u8>::Started.to_string(), "Started");
+    assert_eq!(Event::<u8>::Stopped(3).to_string(), "stopped after 3s");
+    assert_eq!(
+        Event::Value { value: "x", source: "sensor" }.to_string(),
+        r#"value "x" from sensor"#,
+    );
+
+    // Debug but not Display.
+    #[derive(Debug)]
+    struct OnlyDebug;
+
+    assert_display::<Event<OnlyDebug>>();
+}
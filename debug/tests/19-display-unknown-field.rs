@@ -0,0 +1,19 @@
+// Placeholders in a display template have to name a field of the struct or
+// variant they belong to.
+
+use derive_debug::CustomDisplay;
+
+#[derive(CustomDisplay)]
+#[display("{name} ({uid})")]
+pub struct User {
+    name: &'static str,
+    id: u32,
+}
+
+#[derive(CustomDisplay)]
+pub enum Event {
+    #[display("stopped after {1}s")]
+    Stopped(u64),
+}
+
+fn main() {}
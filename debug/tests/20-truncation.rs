@@ -0,0 +1,39 @@
+// Huge collections and strings would flood the logs. #[debug(max_items = N)]
+// prints the first N items of any collection whose references iterate over
+// Debug items, followed by a count of the ones left out.
+// #[debug(max_len = N)] does the same for the characters of a string or the
+// bytes of a byte buffer.
+//
+// The wrapper types doing the formatting live in the derive_debug_support
+// crate, which the caller depends on alongside derive_debug.
+
+use derive_debug::CustomDebug;
+use std::collections::VecDeque;
+
+#[derive(CustomDebug)]
+pub struct Batch {
+    #[debug(max_items = 3)]
+    ids: Vec<u32>,
+    #[debug(max_items = 3)]
+    queue: VecDeque<&'static str>,
+    #[debug(max_len = 5)]
+    label: String,
+    #[debug(max_len = 2)]
+    payload: [u8; 4],
+    #[debug(max_len = 16)]
+    short: &'static str,
+}
+
+fn main() {
+    let batch = Batch {
+        ids: (0..100_000).collect(),
+        queue: VecDeque::from(vec!["a", "b"]),
+        label: "ünïcödé label".to_owned(),
+        payload: [1, 2, 3, 4],
+        short: "fits",
+    };
+    assert_eq!(
+        format!("{:?}", batch),
+        r#"Batch { ids: [0, 1, 2, ... (99997 more)], queue: ["a", "b"], label: "ünïcö"... (8 more), payload: [1, 2, ... (2 more)], short: "fits" }"#,
+    );
+}
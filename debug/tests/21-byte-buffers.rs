@@ -0,0 +1,43 @@
+// Byte buffers have built-in field modes, so callers don't have to write a
+// `with` function for the common cases. They accept anything that is
+// AsRef<[u8]>, such as [u8; N], Vec<u8> or &[u8].
+//
+//   - #[debug(hex)] prints contiguous lowercase hex;
+//   - #[debug(hexdump)] prints an offset column, sixteen hex bytes per line
+//     and an ASCII column;
+//   - #[debug(base64)] prints standard padded base64.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Packet {
+    #[debug(hex)]
+    key: [u8; 4],
+    #[debug(base64)]
+    token: Vec<u8>,
+    #[debug(hexdump)]
+    payload: &'static [u8],
+}
+
+#[derive(CustomDebug)]
+pub enum Frame {
+    Raw(#[debug(base64)] Vec<u8>),
+}
+
+fn main() {
+    let packet = Packet {
+        key: [0xde, 0xad, 0xbe, 0xef],
+        token: b"hello".to_vec(),
+        payload: b"Hello, world!\nBye",
+    };
+    assert_eq!(
+        format!("{:?}", packet),
+        "Packet { key: deadbeef, token: aGVsbG8=, payload: \
+         00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 42 79  |Hello, world!.By|\n\
+         00000010  65                                                |e| }",
+    );
+
+    assert_eq!(format!("{:?}", Frame::Raw(b"ab".to_vec())), "Raw(YWI=)");
+    assert_eq!(format!("{:?}", Frame::Raw(b"abc".to_vec())), "Raw(YWJj)");
+    assert_eq!(format!("{:?}", Frame::Raw(vec![])), "Raw()");
+}
@@ -0,0 +1,51 @@
+// Fields behind #[cfg(...)] and attributes behind #[cfg_attr(...)] follow the
+// configuration. The compiler configures the input of a derive before the
+// macro sees it, so a disabled field never reaches the generated impl and an
+// enabled cfg_attr reads exactly like the attribute it expands to.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Config {
+    name: &'static str,
+    #[cfg(any())]
+    disabled: NotDebug,
+    #[cfg_attr(all(), debug = "0x{:02x}")]
+    flags: u8,
+    #[cfg_attr(any(), debug = "0x{:02x}")]
+    mode: u8,
+    #[cfg_attr(all(), debug(skip))]
+    secret: &'static str,
+}
+
+#[derive(CustomDebug)]
+pub enum Event {
+    Tuple(#[cfg(any())] NotDebug, u16),
+    Named {
+        #[cfg(any())]
+        disabled: NotDebug,
+        #[cfg_attr(all(), debug(redact))]
+        enabled: u8,
+    },
+}
+
+pub struct NotDebug;
+
+fn main() {
+    let config = Config {
+        name: "n",
+        flags: 16,
+        mode: 3,
+        secret: "hunter2",
+    };
+    assert_eq!(
+        format!("{:?}", config),
+        r#"Config { name: "n", flags: 0x10, mode: 3, .. }"#,
+    );
+
+    assert_eq!(format!("{:?}", Event::Tuple(1)), "Tuple(1)");
+    assert_eq!(
+        format!("{:?}", Event::Named { enabled: 1 }),
+        "Named { enabled: <redacted> }",
+    );
+}
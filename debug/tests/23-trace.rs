@@ -0,0 +1,26 @@
+// #[debug(trace)] on the type prints the generated impl to stderr while the
+// caller's crate compiles, which helps when the generated code does not
+// compile. The same output can be switched on without touching the source by
+// setting DERIVE_DEBUG_TRACE to a comma-separated list of type names, or to *
+// for every type. Cargo does not know the macro reads that variable, so the
+// crate has to actually be rebuilt for it to take effect.
+//
+// Tracing only adds output on stderr; the impl itself is unchanged.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(trace)]
+pub struct Field {
+    name: &'static str,
+    #[debug = "0b{:08b}"]
+    bitmask: u8,
+}
+
+fn main() {
+    let f = Field {
+        name: "F",
+        bitmask: 0b00011100,
+    };
+    assert_eq!(format!("{:?}", f), r#"Field { name: "F", bitmask: 0b00011100 }"#);
+}
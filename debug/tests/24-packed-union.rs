@@ -0,0 +1,62 @@
+// Fields of a #[repr(packed)] struct may be unaligned, and taking a reference
+// to one is an error. For packed structs the generated impl copies each field
+// it formats into a local and formats the local instead. That requires the
+// fields to be Copy; skipped and redacted fields are never read, so they are
+// exempt.
+//
+// Unions have no way to tell which field is active. With
+// #[debug(union_as = "unsafe_bytes")] the union is printed as its raw bytes.
+// By opting in, the caller promises that every byte of the union is
+// initialized whichever field is active. Fields smaller than the union break
+// that promise and are rejected at compile time, but the check only compares
+// sizes: a field of the right size with padding inside it, such as
+// `(u8, u16)`, still leaves a byte uninitialized, and avoiding that is up to
+// the caller.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[repr(C, packed)]
+pub struct Header {
+    tag: u8,
+    #[debug = "{:#x}"]
+    length: u32,
+    #[debug(skip)]
+    owner: String,
+}
+
+#[derive(CustomDebug)]
+#[repr(packed(2))]
+pub struct Wrapper<T> {
+    flag: bool,
+    value: T,
+}
+
+#[derive(CustomDebug)]
+#[repr(packed)]
+#[debug(transparent)]
+pub struct Port(u16);
+
+#[derive(CustomDebug)]
+#[debug(union_as = "unsafe_bytes")]
+pub union Word {
+    int: u32,
+    bytes: [u8; 4],
+}
+
+fn main() {
+    let header = Header {
+        tag: 1,
+        length: 256,
+        owner: "root".to_owned(),
+    };
+    assert_eq!(format!("{:?}", header), "Header { tag: 1, length: 0x100, .. }");
+
+    let wrapper = Wrapper { flag: true, value: 7u64 };
+    assert_eq!(format!("{:?}", wrapper), "Wrapper { flag: true, value: 7 }");
+
+    assert_eq!(format!("{:?}", Port(80)), "80");
+
+    let word = Word { bytes: [1, 2, 3, 4] };
+    assert_eq!(format!("{:?}", word), "Word { bytes: [1, 2, 3, 4] }");
+}
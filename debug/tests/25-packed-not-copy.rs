@@ -0,0 +1,23 @@
+// A field of a packed struct that gets formatted has to be Copy, because it is
+// copied out of the struct before formatting. The error points at the type of
+// the offending field.
+//
+// A union cannot be printed field by field, so deriving on one without opting
+// in to #[debug(union_as = "unsafe_bytes")] is an error.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[repr(packed)]
+pub struct Header {
+    tag: u8,
+    owner: String,
+}
+
+#[derive(CustomDebug)]
+pub union Word {
+    int: u32,
+    bytes: [u8; 4],
+}
+
+fn main() {}
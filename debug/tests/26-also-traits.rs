@@ -0,0 +1,73 @@
+// #[debug(also = "...")] generates more std::fmt impls next to Debug, each
+// printing the same struct shape but formatting every plain field with its
+// own trait. Register-like structs can then be printed with {:x} or {:b}.
+//
+// The field attributes keep their meaning: skipped and redacted fields stay
+// hidden, renames apply, and a field with a custom format such as
+// #[debug = "{:?}"] is shown exactly as in Debug. That is the way to include
+// fields that don't implement the trait, like strings.
+//
+// Bounds are inferred as for Debug, with the trait being implemented in place
+// of Debug for plain fields.
+//
+// #[debug(compact)] only changes what {:#?} means. For the numeric traits the
+// alternate flag keeps its usual meaning and adds the 0x or 0b prefix; the
+// std struct builders then also lay the fields out one per line.
+
+use derive_debug::CustomDebug;
+use std::fmt::{Binary, LowerHex, UpperHex};
+
+#[derive(CustomDebug)]
+#[debug(also = "LowerHex, UpperHex, Binary")]
+pub struct Status {
+    #[debug = "{:?}"]
+    name: &'static str,
+    control: u8,
+    #[debug(rename = "ADDR")]
+    address: u32,
+    #[debug(skip)]
+    shadow: u32,
+}
+
+#[derive(CustomDebug)]
+#[debug(also = "LowerHex")]
+pub enum Reg<T> {
+    Byte(u8),
+    Word { value: T },
+}
+
+#[derive(CustomDebug)]
+#[debug(compact, also = "LowerHex")]
+pub struct Pair {
+    a: u8,
+    b: u8,
+}
+
+fn assert_lower_hex<F: LowerHex>() {}
+fn assert_upper_hex<F: UpperHex>() {}
+fn assert_binary<F: Binary>() {}
+
+fn main() {
+    let status = Status {
+        name: "ctrl",
+        control: 0b1010,
+        address: 0xbeef,
+        shadow: 0,
+    };
+    assert_eq!(format!("{:?}", status), r#"Status { name: "ctrl", control: 10, ADDR: 48879, .. }"#);
+    assert_eq!(format!("{:x}", status), r#"Status { name: "ctrl", control: a, ADDR: beef, .. }"#);
+    assert_eq!(format!("{:X}", status), r#"Status { name: "ctrl", control: A, ADDR: BEEF, .. }"#);
+    assert_eq!(format!("{:b}", status), r#"Status { name: "ctrl", control: 1010, ADDR: 1011111011101111, .. }"#);
+
+    assert_eq!(format!("{:x}", Reg::<u16>::Byte(255)), "Byte(ff)");
+    assert_eq!(format!("{:x}", Reg::Word { value: 4096u16 }), "Word { value: 1000 }");
+
+    let pair = Pair { a: 255, b: 16 };
+    assert_eq!(format!("{:#?}", pair), "Pair { a: 255, b: 16 }");
+    assert_eq!(format!("{:#x}", pair), "Pair {\n    a: 0xff,\n    b: 0x10,\n}");
+
+    assert_lower_hex::<Status>();
+    assert_upper_hex::<Status>();
+    assert_binary::<Status>();
+    assert_lower_hex::<Reg<u64>>();
+}
@@ -0,0 +1,114 @@
+// #[debug(visit_fields)] also implements derive_debug_support::VisitFields,
+// which hands each field to a FieldVisitor as a name and a &dyn Debug value.
+// Structured loggers can record the fields as key/value pairs instead of one
+// formatted string.
+//
+// The visitor sees exactly what Debug shows: skipped fields are left out,
+// redacted fields are redacted, renames apply and custom formats are used.
+// Tuple fields are named by their index. For an enum the visitor first gets
+// the name of the active variant, renamed as in Debug, then its fields. A
+// unit variant reports its name only.
+
+use derive_debug::CustomDebug;
+use derive_debug_support::{FieldVisitor, VisitFields};
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+#[debug(visit_fields, rename_all = "camelCase")]
+pub struct Request {
+    user_name: String,
+    #[debug(redact)]
+    password: String,
+    #[debug = "0b{:08b}"]
+    flags: u8,
+    #[debug(skip)]
+    cache: Vec<u8>,
+}
+
+#[derive(CustomDebug)]
+#[debug(visit_fields, rename_all = "snake_case")]
+pub enum Event<T> {
+    Start(T, u32),
+    Restart(T, u32),
+    Stop { code: i32 },
+    Idle,
+}
+
+#[derive(Default)]
+struct Recorder {
+    variant: Option<String>,
+    fields: Vec<(String, String)>,
+}
+
+impl FieldVisitor for Recorder {
+    fn visit_variant(&mut self, name: &str) {
+        self.variant = Some(name.to_owned());
+    }
+
+    fn visit_field(&mut self, name: &str, value: &dyn Debug) {
+        self.fields.push((name.to_owned(), format!("{:?}", value)));
+    }
+}
+
+// A visitor that only sees structs needs no visit_variant
+#[derive(Default)]
+struct Names(Vec<String>);
+
+impl FieldVisitor for Names {
+    fn visit_field(&mut self, name: &str, _value: &dyn Debug) {
+        self.0.push(name.to_owned());
+    }
+}
+
+fn record(value: &dyn VisitFields) -> Vec<(String, String)> {
+    let mut recorder = Recorder::default();
+    value.visit(&mut recorder);
+    assert_eq!(recorder.variant, None);
+    recorder.fields
+}
+
+fn record_variant(value: &dyn VisitFields) -> (String, Vec<(String, String)>) {
+    let mut recorder = Recorder::default();
+    value.visit(&mut recorder);
+    (recorder.variant.expect("no variant reported"), recorder.fields)
+}
+
+fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+    expected.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+fn main() {
+    let request = Request {
+        user_name: "ferris".to_owned(),
+        password: "hunter2".to_owned(),
+        flags: 5,
+        cache: vec![],
+    };
+    assert_eq!(
+        format!("{:?}", request),
+        r#"Request { userName: "ferris", password: <redacted>, flags: 0b00000101, .. }"#,
+    );
+    assert_eq!(
+        record(&request),
+        pairs(&[("userName", r#""ferris""#), ("password", "<redacted>"), ("flags", "0b00000101")]),
+    );
+
+    let mut names = Names::default();
+    request.visit(&mut names);
+    assert_eq!(names.0, ["userName", "password", "flags"]);
+
+    assert_eq!(
+        record_variant(&Event::Start("job", 7)),
+        ("start".to_owned(), pairs(&[("0", r#""job""#), ("1", "7")])),
+    );
+    assert_eq!(
+        record_variant(&Event::Restart("job", 7)),
+        ("restart".to_owned(), pairs(&[("0", r#""job""#), ("1", "7")])),
+    );
+    assert_eq!(
+        record_variant(&Event::<()>::Stop { code: -1 }),
+        ("stop".to_owned(), pairs(&[("code", "-1")])),
+    );
+    assert_eq!(format!("{:?}", Event::<()>::Idle), "idle");
+    assert_eq!(record_variant(&Event::<()>::Idle), ("idle".to_owned(), pairs(&[])));
+}
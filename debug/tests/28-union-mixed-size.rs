@@ -0,0 +1,22 @@
+// A union whose fields differ in size leaves some bytes uninitialized while
+// the smaller field is active, and printing them would be undefined behavior.
+// union_as = "unsafe_bytes" checks at compile time that every field is as
+// large as the union, and the plain "bytes" spelling is refused.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(union_as = "unsafe_bytes")]
+pub union Mixed {
+    small: u8,
+    large: u32,
+}
+
+#[derive(CustomDebug)]
+#[debug(union_as = "bytes")]
+pub union Word {
+    int: u32,
+    bytes: [u8; 4],
+}
+
+fn main() {}
@@ -6,7 +6,27 @@ fn tests() {
     t.pass("tests/03-custom-format.rs");
     t.pass("tests/04-type-parameter.rs");
     t.pass("tests/05-phantom-data.rs");
-    // t.pass("tests/06-bound-trouble.rs");
-    // t.pass("tests/07-associated-type.rs");
-    // t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/06-bound-trouble.rs");
+    t.pass("tests/07-associated-type.rs");
+    t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/09-tuple-unit-enum.rs");
+    t.pass("tests/10-skip-redact.rs");
+    t.pass("tests/11-with-fn.rs");
+    t.pass("tests/12-nested-bounds.rs");
+    t.pass("tests/13-field-bound.rs");
+    t.compile_fail("tests/14-invalid-bound.rs");
+    t.compile_fail("tests/15-malformed-attribute.rs");
+    t.pass("tests/16-compact-transparent.rs");
+    t.pass("tests/17-rename.rs");
+    t.pass("tests/18-display.rs");
+    t.compile_fail("tests/19-display-unknown-field.rs");
+    t.pass("tests/20-truncation.rs");
+    t.pass("tests/21-byte-buffers.rs");
+    t.pass("tests/22-cfg.rs");
+    t.pass("tests/23-trace.rs");
+    t.pass("tests/24-packed-union.rs");
+    t.compile_fail("tests/25-packed-not-copy.rs");
+    t.pass("tests/26-also-traits.rs");
+    t.pass("tests/27-visit-fields.rs");
+    t.compile_fail("tests/28-union-mixed-size.rs");
 }
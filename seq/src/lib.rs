@@ -1,8 +1,685 @@
 use proc_macro::TokenStream;
+use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, TokenTree};
+use syn::parse::{Parse, ParseStream};
+use syn::parse_macro_input;
+use syn::punctuated::Punctuated;
+use quote::{quote, ToTokens};
+
+// seq!(N in 0..8 { ... })
+// seq!(R in 0..4, C in 0..4 { ... })
+struct Seq {
+    loops: Vec<Loop>,
+    body: proc_macro2::TokenStream,
+    // seq!(N in 0..NPROC { ... })
+    named: Option<(Ident, Range)>,
+}
+
+// N in 0..8
+// T in [u8, u16]
+// (T, N) in [(u8, 8), (u16, 16)]
+struct Loop {
+    vars: Vec<Ident>,
+    // one value per variable in each row
+    values: Vec<Vec<Value>>,
+}
+
+enum Value {
+    Int(u64, LitFormat),
+    // anything else from a list, such as `u8` or `"name"`
+    Tokens(proc_macro2::TokenStream),
+}
+
+// 8, u8, Vec<u8>, "name"
+fn parse_value(input: ParseStream) -> syn::Result<Value> {
+    if input.peek(syn::LitInt) {
+        let lit: syn::LitInt = input.parse()?;
+        return Ok(Value::Int(lit.base10_parse()?, lit_format(&lit, lit.suffix())));
+    }
+    if input.peek(syn::Lit) {
+        return Ok(Value::Tokens(input.parse::<syn::Lit>()?.into_token_stream()));
+    }
+    Ok(Value::Tokens(input.parse::<syn::Type>()?.into_token_stream()))
+}
+
+// [u8, u16] or, for a tuple of variables, [(u8, 8), (u16, 16)]
+fn parse_list(input: ParseStream, vars: &[Ident]) -> syn::Result<Vec<Vec<Value>>> {
+    let content;
+    syn::bracketed!(content in input);
+    let mut rows = vec![];
+    while !content.is_empty() {
+        if vars.len() == 1 {
+            rows.push(vec![parse_value(&content)?]);
+        } else {
+            let tuple;
+            let paren = syn::parenthesized!(tuple in content);
+            let row = Punctuated::<Value, syn::Token![,]>::parse_terminated_with(&tuple, parse_value)?;
+            if row.len() != vars.len() {
+                return Err(syn::Error::new(paren.span, format!("expected a tuple of {} values", vars.len())));
+            }
+            rows.push(row.into_iter().collect());
+        }
+        if content.is_empty() {
+            break;
+        }
+        content.parse::<syn::Token![,]>()?;
+    }
+    Ok(rows)
+}
+
+// How the values are written out: 0x00..0x10 gives 0x00, 0x01, ... and
+// 0u8..16u8 gives 0u8, 1u8, ...
+#[derive(Clone)]
+struct LitFormat {
+    prefix: &'static str,
+    radix: u32,
+    width: usize,
+    upper: bool,
+    suffix: String,
+}
+
+impl LitFormat {
+    fn literal(&self, value: u64, span: proc_macro2::Span) -> Literal {
+        let width = self.width;
+        let digits = match (self.radix, self.upper) {
+            (16, true) => format!("{:0width$X}", value),
+            (16, false) => format!("{:0width$x}", value),
+            (8, _) => format!("{:0width$o}", value),
+            (2, _) => format!("{:0width$b}", value),
+            _ => format!("{:0width$}", value),
+        };
+        let mut literal: Literal = format!("{}{}{}", self.prefix, digits, self.suffix).parse().unwrap();
+        literal.set_span(span);
+        literal
+    }
+}
+
+impl Parse for Seq {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut loops: Vec<Loop> = vec![];
+        loop {
+            let mut vars: Vec<Ident> = if input.peek(syn::token::Paren) {
+                let content;
+                syn::parenthesized!(content in input);
+                Punctuated::<Ident, syn::Token![,]>::parse_terminated(&content)?.into_iter().collect()
+            } else {
+                vec![input.parse()?]
+            };
+            for (i, var) in vars.iter().enumerate() {
+                if vars[..i].contains(var) || loops.iter().any(|l| l.vars.contains(var)) {
+                    return Err(syn::Error::new(var.span(), "duplicate loop variable"));
+                }
+            }
+            input.parse::<syn::Token![in]>()?;
+            if loops.is_empty() && vars.len() == 1 && named_range_follows(input) {
+                let range = input.parse()?;
+                let content;
+                syn::braced!(content in input);
+                return Ok(Seq {
+                    loops,
+                    body: content.parse()?,
+                    named: Some((vars.remove(0), range)),
+                });
+            }
+            let values = if input.peek(syn::token::Bracket) {
+                parse_list(input, &vars)?
+            } else if vars.len() != 1 {
+                return Err(input.error("expected a list of tuples"));
+            } else {
+                let (values, format) = parse_values(input)?;
+                values.into_iter().map(|value| vec![Value::Int(value, format.clone())]).collect()
+            };
+            loops.push(Loop { vars, values });
+            if !input.peek(syn::Token![,]) {
+                break;
+            }
+            input.parse::<syn::Token![,]>()?;
+        }
+        let content;
+        syn::braced!(content in input);
+        let body = content.parse()?;
+        Ok(Seq {
+            loops,
+            body,
+            named: None,
+        })
+    }
+}
+
+// 0..8, 0..=8
+struct Range {
+    start: Bound,
+    end: Bound,
+    inclusive: bool,
+}
+
+enum Bound {
+    Lit(syn::LitInt),
+    // NPROC, consts::NPROC
+    Named(syn::Path),
+}
+
+impl Parse for Bound {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(syn::LitInt) {
+            Ok(Bound::Lit(input.parse()?))
+        } else {
+            Ok(Bound::Named(input.parse()?))
+        }
+    }
+}
+
+impl ToTokens for Bound {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self {
+            Bound::Lit(lit) => lit.to_tokens(tokens),
+            Bound::Named(path) => path.to_tokens(tokens),
+        }
+    }
+}
+
+impl Bound {
+    fn lit(&self) -> syn::Result<&syn::LitInt> {
+        match self {
+            Bound::Lit(lit) => Ok(lit),
+            Bound::Named(path) => Err(syn::Error::new_spanned(path, "expected an integer literal, a named constant only works as the range of a single loop with no step or rev")),
+        }
+    }
+}
+
+// A range with a named constant at either end, directly followed by the body
+fn named_range_follows(input: ParseStream) -> bool {
+    let fork = input.fork();
+    match fork.parse::<Range>() {
+        Ok(range) => {
+            let named = matches!(range.start, Bound::Named(_)) || matches!(range.end, Bound::Named(_));
+            named && fork.peek(syn::token::Brace)
+        }
+        Err(_) => false,
+    }
+}
+
+fn lit_format(lit: &syn::LitInt, suffix: &str) -> LitFormat {
+    let text = lit.to_string();
+    let text = &text[..text.len() - lit.suffix().len()];
+    let (prefix, radix) = match text.get(..2) {
+        Some("0x") => ("0x", 16),
+        Some("0o") => ("0o", 8),
+        Some("0b") => ("0b", 2),
+        _ => ("", 10),
+    };
+    let digits = text[prefix.len()..].replace('_', "");
+    LitFormat {
+        prefix,
+        radix,
+        // 0x00 keeps its two digits, 7 is not padded
+        width: if digits.starts_with('0') { digits.len() } else { 1 },
+        upper: digits.chars().any(|c| c.is_ascii_uppercase()),
+        suffix: suffix.to_owned(),
+    }
+}
+
+impl Parse for Range {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let start = input.parse()?;
+        let inclusive = input.peek(syn::Token![..=]);
+        if inclusive {
+            input.parse::<syn::Token![..=]>()?;
+        } else {
+            input.parse::<syn::Token![..]>()?;
+        }
+        let end = input.parse()?;
+        Ok(Range { start, end, inclusive })
+    }
+}
+
+impl Range {
+    // the format of the start literal, with the suffix of either end
+    fn format(&self) -> syn::Result<LitFormat> {
+        let (start, end) = (self.start.lit()?, self.end.lit()?);
+        let suffix = match (start.suffix(), end.suffix()) {
+            (start, end_suffix) if !start.is_empty() && !end_suffix.is_empty() && start != end_suffix => {
+                return Err(syn::Error::new(end.span(), format!("expected suffix `{}` to match the range start", start)));
+            }
+            ("", end) => end,
+            (start, _) => start,
+        };
+        Ok(lit_format(start, suffix))
+    }
+
+    fn values(&self, reversed: bool) -> syn::Result<Vec<u64>> {
+        let start = self.start.lit()?.base10_parse::<u64>()?;
+        let end = self.end.lit()?.base10_parse::<u64>()?;
+        // 16..0 is empty, which is never what was meant
+        if start > end && !reversed {
+            return Err(syn::Error::new(self.start.lit()?.span(), "range start is greater than range end, use `(a..b).rev()` on an ascending range to count down"));
+        }
+        Ok(if self.inclusive {
+            (start..=end).collect()
+        } else {
+            (start..end).collect()
+        })
+    }
+}
+
+fn parse_step(input: ParseStream) -> syn::Result<usize> {
+    let step: syn::LitInt = input.parse()?;
+    match step.base10_parse::<usize>()? {
+        0 => Err(syn::Error::new(step.span(), "step must be greater than zero")),
+        step => Ok(step),
+    }
+}
+
+// 0..64
+// 0..=255 step 8
+// (0..64).step_by(4)
+// (0..16).rev()
+fn parse_values(input: ParseStream) -> syn::Result<(Vec<u64>, LitFormat)> {
+    if !input.peek(syn::token::Paren) {
+        let range: Range = input.parse()?;
+        let mut values = range.values(false)?;
+        if input.peek(syn::Ident) {
+            let step: Ident = input.parse()?;
+            if step != "step" {
+                return Err(syn::Error::new(step.span(), "expected `step` or `{`"));
+            }
+            let step = parse_step(input)?;
+            values = values.into_iter().step_by(step).collect();
+        }
+        return Ok((values, range.format()?));
+    }
+
+    let content;
+    syn::parenthesized!(content in input);
+    let range: Range = content.parse()?;
+    // methods apply in order, as they would on the iterator
+    let mut methods = vec![];
+    while input.peek(syn::Token![.]) {
+        input.parse::<syn::Token![.]>()?;
+        let method: Ident = input.parse()?;
+        let args;
+        syn::parenthesized!(args in input);
+        match method.to_string().as_str() {
+            "step_by" => methods.push(Some(parse_step(&args)?)),
+            "rev" => methods.push(None),
+            _ => return Err(syn::Error::new(method.span(), "expected `step_by` or `rev`")),
+        }
+        if !args.is_empty() {
+            return Err(args.error("unexpected argument"));
+        }
+    }
+    let mut values = range.values(methods.contains(&None))?;
+    for method in methods {
+        values = match method {
+            Some(step) => values.into_iter().step_by(step).collect(),
+            None => values.into_iter().rev().collect(),
+        };
+    }
+    Ok((values, range.format()?))
+}
+
+// #( ... )*
+// #( ... ),*
+struct Section<'a> {
+    group: &'a Group,
+    // the punctuation between `)` and `*`, possibly none or several joined
+    // characters such as `&&`
+    separator: &'a [TokenTree],
+    // number of tokens from `#` to `*`
+    len: usize,
+}
+
+fn repeat_section(tokens: &[TokenTree]) -> Option<Section<'_>> {
+    let group = match tokens {
+        [TokenTree::Punct(pound), TokenTree::Group(group), ..] if pound.as_char() == '#' && group.delimiter() == Delimiter::Parenthesis => group,
+        _ => return None,
+    };
+    for (n, token) in tokens[2..].iter().enumerate() {
+        match token {
+            TokenTree::Punct(star) if star.as_char() == '*' => {
+                return Some(Section { group, separator: &tokens[2..2 + n], len: n + 3 });
+            }
+            TokenTree::Punct(punct) if punct.spacing() == Spacing::Joint || n == 0 => {}
+            _ => return None,
+        }
+    }
+    None
+}
+
+fn has_repeat_section(body: &proc_macro2::TokenStream) -> bool {
+    let tokens: Vec<TokenTree> = body.clone().into_iter().collect();
+    (0..tokens.len()).any(|i| match &tokens[i] {
+        TokenTree::Group(group) => has_repeat_section(&group.stream()),
+        _ => repeat_section(&tokens[i..]).is_some(),
+    })
+}
+
+// The separator no longer joins with the `*` that followed it
+fn separator(tokens: &[TokenTree]) -> Vec<TokenTree> {
+    let mut separator = tokens.to_vec();
+    if let Some(TokenTree::Punct(last)) = separator.last_mut() {
+        let mut alone = Punct::new(last.as_char(), Spacing::Alone);
+        alone.set_span(last.span());
+        *last = alone;
+    }
+    separator
+}
+
+// #len or #count
+fn count_keyword(tokens: &[TokenTree]) -> Option<&Ident> {
+    match tokens {
+        [TokenTree::Punct(pound), TokenTree::Ident(keyword), ..] if pound.as_char() == '#' && (keyword == "len" || keyword == "count") => Some(keyword),
+        _ => None,
+    }
+}
+
+// The value each loop variable currently stands for
+type Bindings<'a> = Vec<(&'a Ident, &'a Value)>;
+
+// every combination of values, the last loop varying fastest
+fn product(loops: &[Loop]) -> Vec<Bindings<'_>> {
+    let mut combinations = vec![vec![]];
+    for l in loops {
+        combinations = combinations.into_iter()
+            .flat_map(|bindings: Bindings| l.values.iter().map(move |row| {
+                let mut bindings = bindings.clone();
+                bindings.extend(l.vars.iter().zip(row));
+                bindings
+            }))
+            .collect();
+    }
+    combinations
+}
+
+fn lookup<'a>(bindings: &Bindings<'a>, ident: &Ident) -> Option<&'a Value> {
+    bindings.iter().find(|(var, _)| *var == ident).map(|&(_, value)| value)
+}
+
+// the text a value contributes to a pasted identifier
+fn paste(value: &Value, width: Option<usize>, span: proc_macro2::Span) -> syn::Result<String> {
+    match value {
+        Value::Int(value, _) => Ok(format!("{:0width$}", value, width = width.unwrap_or(1))),
+        Value::Tokens(tokens) => match syn::parse2::<Ident>(tokens.clone()) {
+            Ok(ident) if width.is_none() => Ok(ident.to_string()),
+            _ => Err(syn::Error::new(span, "only identifiers and integers can be pasted")),
+        },
+    }
+}
+
+// #{N * 4}, with the loop variables standing for their current values
+fn eval(expr: &syn::Expr, bindings: &Bindings) -> syn::Result<u64> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(lit), .. }) => lit.base10_parse(),
+        syn::Expr::Path(path) => path.path.get_ident()
+            .and_then(|ident| lookup(bindings, ident))
+            .and_then(|value| match value {
+                Value::Int(value, _) => Some(*value),
+                Value::Tokens(_) => None,
+            })
+            .ok_or_else(|| syn::Error::new_spanned(path, "expected an integer loop variable")),
+        syn::Expr::Paren(paren) => eval(&paren.expr, bindings),
+        syn::Expr::Binary(binary) => {
+            let left = eval(&binary.left, bindings)?;
+            let right = eval(&binary.right, bindings)?;
+            let value = match binary.op {
+                syn::BinOp::Add(_) => left.checked_add(right),
+                syn::BinOp::Sub(_) => left.checked_sub(right),
+                syn::BinOp::Mul(_) => left.checked_mul(right),
+                syn::BinOp::Div(_) => left.checked_div(right),
+                syn::BinOp::Rem(_) => left.checked_rem(right),
+                syn::BinOp::BitAnd(_) => Some(left & right),
+                syn::BinOp::BitOr(_) => Some(left | right),
+                syn::BinOp::BitXor(_) => Some(left ^ right),
+                // shifting bits out of the top is an overflow too
+                syn::BinOp::Shl(_) => u32::try_from(right).ok()
+                    .and_then(|right| left.checked_shl(right))
+                    .filter(|value| value >> right == left),
+                syn::BinOp::Shr(_) => u32::try_from(right).ok().and_then(|right| left.checked_shr(right)),
+                _ => return Err(syn::Error::new_spanned(binary.op, "unsupported operator")),
+            };
+            value.ok_or_else(|| syn::Error::new_spanned(binary, "arithmetic overflow or division by zero"))
+        }
+        _ => Err(syn::Error::new_spanned(expr, "expected an integer expression")),
+    }
+}
+
+fn eval_group(group: &Group, bindings: &Bindings) -> syn::Result<u64> {
+    eval(&syn::parse2(group.stream())?, bindings)
+}
+
+impl Seq {
+    // The outermost `#( ... )*` repeats over the first loop variable, a
+    // section nested in it over the second, and so on. The innermost section
+    // repeats over all the variables that are left.
+    fn expand_sections(&self, body: proc_macro2::TokenStream, depth: usize, bindings: &Bindings) -> syn::Result<proc_macro2::TokenStream> {
+        let tokens: Vec<TokenTree> = body.into_iter().collect();
+        let mut output = proc_macro2::TokenStream::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            if let Some(section) = repeat_section(&tokens[i..]) {
+                let body = section.group.stream();
+                let loops = if depth >= self.loops.len() {
+                    return Err(syn::Error::new(section.group.span(), "no loop variable left for this repetition"));
+                } else if has_repeat_section(&body) {
+                    &self.loops[depth..depth + 1]
+                } else {
+                    &self.loops[depth..]
+                };
+                // like macro_rules, the separator goes between repetitions
+                for (n, inner) in product(loops).into_iter().enumerate() {
+                    if n > 0 {
+                        output.extend(separator(section.separator));
+                    }
+                    let mut bindings = bindings.clone();
+                    bindings.extend(inner);
+                    output.extend(self.expand_sections(body.clone(), depth + loops.len(), &bindings)?);
+                }
+                i += section.len;
+                continue;
+            }
+            // the number of repetitions of the loops not bound yet
+            if let Some(keyword) = count_keyword(&tokens[i..]) {
+                let count = self.loops.get(depth..).unwrap_or_default().iter().map(|l| l.values.len() as u64).product();
+                let mut literal = Literal::u64_unsuffixed(count);
+                literal.set_span(keyword.span());
+                output.extend(std::iter::once(TokenTree::Literal(literal)));
+                i += 2;
+                continue;
+            }
+            if let Some(expr) = const_expr(&tokens[i..]) {
+                let mut literal = Literal::u64_unsuffixed(eval_group(expr, bindings)?);
+                literal.set_span(expr.span());
+                output.extend(std::iter::once(TokenTree::Literal(literal)));
+                i += 2;
+                continue;
+            }
+            let token = match &tokens[i] {
+                TokenTree::Group(group) => {
+                    let mut expanded = Group::new(group.delimiter(), self.expand_sections(group.stream(), depth, bindings)?);
+                    expanded.set_span(group.span());
+                    TokenTree::Group(expanded)
+                }
+                TokenTree::Ident(ident) if pasted(&tokens[i..]) => {
+                    // the pasted ident keeps the span of its first part, so
+                    // errors point at the name the user wrote
+                    let mut name = match lookup(bindings, ident) {
+                        Some(value) => paste(value, None, ident.span())?,
+                        None => ident.to_string(),
+                    };
+                    while let [TokenTree::Punct(tilde), part, ..] = &tokens[i + 1..] {
+                        if tilde.as_char() != '~' {
+                            break;
+                        }
+                        match part {
+                            TokenTree::Ident(part) => match lookup(bindings, part) {
+                                Some(value) => {
+                                    let width = padding(&tokens[i + 3..]);
+                                    name.push_str(&paste(value, width, part.span())?);
+                                    if width.is_some() {
+                                        i += 2;
+                                    }
+                                }
+                                None => name.push_str(&part.to_string()),
+                            },
+                            TokenTree::Group(expr) if expr.delimiter() == Delimiter::Brace => {
+                                name.push_str(&eval_group(expr, bindings)?.to_string());
+                            }
+                            _ => break,
+                        }
+                        i += 2;
+                    }
+                    if name.starts_with(|c: char| c.is_ascii_digit()) {
+                        return Err(syn::Error::new(ident.span(), format!("`{}` is not a valid identifier", name)));
+                    }
+                    TokenTree::Ident(Ident::new(&name, ident.span()))
+                }
+                TokenTree::Ident(ident) => match lookup(bindings, ident) {
+                    Some(Value::Int(value, format)) => TokenTree::Literal(format.literal(*value, ident.span())),
+                    Some(Value::Tokens(tokens)) => {
+                        output.extend(tokens.clone());
+                        i += 1;
+                        continue;
+                    }
+                    None => TokenTree::Ident(ident.clone()),
+                },
+                token => token.clone(),
+            };
+            output.extend(std::iter::once(token));
+            i += 1;
+        }
+        Ok(output)
+    }
+
+    // The value of NPROC in seq!(N in 0..NPROC { ... }) is unknown to the
+    // macro, so nothing can be unrolled. An array body `[#( elem, )*]` is
+    // instead filled by a loop, with N a usize, which also works in a const
+    // or static initializer.
+    fn expand_named(&self, var: &Ident, range: &Range) -> syn::Result<proc_macro2::TokenStream> {
+        let tokens: Vec<TokenTree> = self.body.clone().into_iter().collect();
+        let section = match tokens.as_slice() {
+            [TokenTree::Group(array)] if array.delimiter() == Delimiter::Bracket => {
+                let inner: Vec<TokenTree> = array.stream().into_iter().collect();
+                repeat_section(&inner)
+                    .filter(|section| section.len == inner.len())
+                    .filter(|section| match section.separator {
+                        [] => true,
+                        [TokenTree::Punct(comma)] => comma.as_char() == ',',
+                        _ => false,
+                    })
+                    .map(|section| section.group.clone())
+            }
+            _ => None,
+        };
+        let named = match (&range.start, &range.end) {
+            (Bound::Named(path), _) | (_, Bound::Named(path)) => path,
+            _ => unreachable!(),
+        };
+        let section = section.ok_or_else(|| syn::Error::new_spanned(named, "with a named constant as range end, the body has to be an array `[#( ... )*]`"))?;
+        if uses_expansion(&section.stream()) {
+            return Err(syn::Error::new(section.span(), "pasting, #{...}, #len and nested repetitions need integer literals as range ends"));
+        }
+        let mut element: Vec<TokenTree> = section.stream().into_iter().collect();
+        if matches!(element.last(), Some(TokenTree::Punct(comma)) if comma.as_char() == ',') {
+            element.pop();
+        }
+
+        let (start, end) = (&range.start, &range.end);
+        let len = if range.inclusive {
+            quote!((#end) as usize - (#start) as usize + 1)
+        } else {
+            quote!((#end) as usize - (#start) as usize)
+        };
+        // No item of our own may name the range ends, they can be generic
+        // parameters of the enclosing fn, which only a bare `N` can stand for
+        // in an array length. For 0..N that is the length; for other ranges it
+        // is inferred from where the array goes and checked against the range.
+        let array_len = match (start, range.inclusive) {
+            (Bound::Lit(start), false) if start.base10_parse::<u64>().ok() == Some(0) => quote!(#end),
+            _ => quote!(_),
+        };
+        Ok(quote!({
+            const unsafe fn __seq_assume_init<T, const LEN: usize>(array: [::core::mem::MaybeUninit<T>; LEN]) -> [T; LEN] {
+                unsafe { (&array as *const [::core::mem::MaybeUninit<T>; LEN] as *const [T; LEN]).read() }
+            }
+            let mut __seq_array: [::core::mem::MaybeUninit<_>; #array_len] = unsafe {
+                ::core::mem::MaybeUninit::uninit().assume_init()
+            };
+            assert!(__seq_array.len() == #len, "the seq! range and the array differ in length");
+            let mut __seq_index = 0;
+            while __seq_index < __seq_array.len() {
+                #[allow(non_snake_case)]
+                let #var: usize = (#start) as usize + __seq_index;
+                __seq_array[__seq_index] = ::core::mem::MaybeUninit::new(#(#element)*);
+                __seq_index += 1;
+            }
+            unsafe { __seq_assume_init(__seq_array) }
+        }))
+    }
+
+    // Without any `#( ... )*` the whole body is repeated for every
+    // combination of values.
+    fn expand(&self) -> syn::Result<proc_macro2::TokenStream> {
+        if let Some((var, range)) = &self.named {
+            return self.expand_named(var, range);
+        }
+        if has_repeat_section(&self.body) {
+            return self.expand_sections(self.body.clone(), 0, &vec![]);
+        }
+        let mut output = proc_macro2::TokenStream::new();
+        for bindings in product(&self.loops) {
+            output.extend(self.expand_sections(self.body.clone(), 0, &bindings)?);
+        }
+        Ok(output)
+    }
+}
+
+// Irq~N, Reg~{N * 4}
+fn pasted(tokens: &[TokenTree]) -> bool {
+    match tokens {
+        [TokenTree::Ident(_), TokenTree::Punct(tilde), TokenTree::Ident(_), ..] => tilde.as_char() == '~',
+        [TokenTree::Ident(_), TokenTree::Punct(tilde), TokenTree::Group(expr), ..] => tilde.as_char() == '~' && expr.delimiter() == Delimiter::Brace,
+        _ => false,
+    }
+}
+
+// Irq~N:02, the padding has to start with 0 so that a field initializer
+// such as `S { f~N: 1 }` keeps its meaning
+fn padding(tokens: &[TokenTree]) -> Option<usize> {
+    match tokens {
+        [TokenTree::Punct(colon), TokenTree::Literal(width), ..] if colon.as_char() == ':' => {
+            let width = width.to_string();
+            if width.len() > 1 && width.starts_with('0') && width.bytes().all(|b| b.is_ascii_digit()) {
+                width.parse().ok()
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+// anything that needs the value of the loop variable at expansion time
+fn uses_expansion(body: &proc_macro2::TokenStream) -> bool {
+    let tokens: Vec<TokenTree> = body.clone().into_iter().collect();
+    (0..tokens.len()).any(|i| match &tokens[i] {
+        TokenTree::Group(group) => uses_expansion(&group.stream()),
+        TokenTree::Punct(tilde) if tilde.as_char() == '~' => true,
+        _ => repeat_section(&tokens[i..]).is_some() || const_expr(&tokens[i..]).is_some() || count_keyword(&tokens[i..]).is_some(),
+    })
+}
+
+// #{N * 4}
+fn const_expr(tokens: &[TokenTree]) -> Option<&Group> {
+    match tokens {
+        [TokenTree::Punct(pound), TokenTree::Group(expr), ..] if pound.as_char() == '#' && expr.delimiter() == Delimiter::Brace => Some(expr),
+        _ => None,
+    }
+}
 
 #[proc_macro]
 pub fn seq(input: TokenStream) -> TokenStream {
-    let _ = input;
-
-    unimplemented!()
+    let seq = parse_macro_input!(input as Seq);
+    match seq.expand() {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
 }
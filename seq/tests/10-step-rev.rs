@@ -0,0 +1,34 @@
+// The range in the header can be stepped or reversed, using the same methods
+// as on an iterator: (0..64).step_by(4) or (0..16).rev(). Methods apply in the
+// order they are written. A bare range can also be stepped with `step N`.
+
+use seq::seq;
+
+seq!(N in (0..64).step_by(16) {
+    const OFFSETS: [usize; 4] = [#(N,)*];
+});
+
+seq!(N in (0..4).rev() {
+    const COUNTDOWN: [usize; 4] = [#(N,)*];
+});
+
+seq!(N in (0..10).step_by(3).rev() {
+    const STEPPED_DOWN: [usize; 4] = [#(N,)*];
+});
+
+seq!(N in 0..=255 step 64 {
+    #[allow(dead_code)]
+    enum Bank {
+        #(
+            Reg~N,
+        )*
+    }
+});
+
+fn main() {
+    assert_eq!(OFFSETS, [0, 16, 32, 48]);
+    assert_eq!(COUNTDOWN, [3, 2, 1, 0]);
+    assert_eq!(STEPPED_DOWN, [9, 6, 3, 0]);
+
+    let _ = [Bank::Reg0, Bank::Reg64, Bank::Reg128, Bank::Reg192];
+}
@@ -0,0 +1,11 @@
+// A zero step would never make progress, and a range whose start is past its
+// end expands to nothing, which is almost certainly a typo for a countdown.
+// Both are reported at the offending literal.
+
+use seq::seq;
+
+seq!(N in (0..64).step_by(0) {});
+
+seq!(N in 16..0 {});
+
+fn main() {}
@@ -0,0 +1,41 @@
+// Several loop variables can be declared in one header, separated by commas.
+// The body is expanded for every combination of their values, the last
+// variable varying fastest, so 2D tables don't need nested seq! calls.
+//
+// Repetition sections are bound to the variables in order: the outermost
+// #(...)* repeats over R and a section nested inside it repeats over C. The
+// innermost section repeats over every variable that is left, so a single
+// section in a two-variable seq! walks the whole grid.
+
+use seq::seq;
+
+seq!(R in 0..2, C in 0..3 {
+    #[allow(dead_code)]
+    enum Cell {
+        #(
+            Cell~R~C,
+        )*
+    }
+
+    const GRID: [[(usize, usize); 3]; 2] = [
+        #(
+            [#((R, C),)*],
+        )*
+    ];
+});
+
+seq!(X in 0..2, Y in 0..2 {
+    const fn point~X~Y() -> (u8, u8) {
+        (X, Y)
+    }
+});
+
+fn main() {
+    let _ = [Cell::Cell00, Cell::Cell01, Cell::Cell02, Cell::Cell10, Cell::Cell11, Cell::Cell12];
+
+    assert_eq!(GRID[1][2], (1, 2));
+    assert_eq!(GRID[0], [(0, 0), (0, 1), (0, 2)]);
+
+    assert_eq!(point01(), (0, 1));
+    assert_eq!(point11(), (1, 1));
+}
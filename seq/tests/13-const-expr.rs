@@ -0,0 +1,34 @@
+// #{...} evaluates a small integer expression at expansion time and expands to
+// the resulting literal. The expression can use the loop variables, integer
+// literals, parentheses and the operators + - * / % << >> & | ^.
+//
+// An expression in braces can also be pasted into an identifier, as in
+// Reg~{N * 4}.
+
+use seq::seq;
+
+seq!(N in 0..4 {
+    const MASKS: [u8; 4] = [#(#{1 << N},)*];
+    const OFFSETS: [usize; 4] = [#(#{N * 4 + 0x100},)*];
+
+    #[allow(dead_code)]
+    enum Reg {
+        #(
+            Reg~{N * 4} = #{N * 4},
+        )*
+    }
+});
+
+seq!(N in 1..3 {
+    const fn next~N() -> u32 {
+        #{(N + 1) % 2}
+    }
+});
+
+fn main() {
+    assert_eq!(MASKS, [1, 2, 4, 8]);
+    assert_eq!(OFFSETS, [0x100, 0x104, 0x108, 0x10c]);
+    assert_eq!(Reg::Reg12 as u8, 12);
+    assert_eq!(next1(), 0);
+    assert_eq!(next2(), 1);
+}
@@ -0,0 +1,14 @@
+// Arithmetic in #{...} is checked. An expression that overflows, underflows
+// or divides by zero is reported at the operation that failed.
+
+use seq::seq;
+
+seq!(N in 0..2 {
+    const A: u64 = #{N - 1};
+});
+
+seq!(N in 60..61 {
+    const B: u64 = #{1 + (N << 60)};
+});
+
+fn main() {}
@@ -0,0 +1,55 @@
+// The loop variable is written out in the same format as the start of the
+// range. A suffix on either end is kept, so 0u8..4u8 gives u8 literals, and
+// hex, octal or binary ranges keep their radix and zero padding: 0x00..0x10
+// gives 0x00, 0x01, ... 0x0f.
+//
+// When pasting, a zero-padded width can follow the variable: Irq~N:02 gives
+// Irq00, Irq01, ... so that generated names sort in numeric order.
+
+use seq::seq;
+
+fn type_name<T>(_: T) -> &'static str {
+    std::any::type_name::<T>()
+}
+
+seq!(N in 0u8..4u8 {
+    const SUFFIXED: [&str; 4] = [#(stringify!(N),)*];
+    const fn first() -> &'static str {
+        stringify!(#(N)*)
+    }
+});
+
+seq!(N in 0x08..0x0C {
+    const HEX: [&str; 4] = [#(stringify!(N),)*];
+});
+
+seq!(N in 0..12 {
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    enum Irq {
+        #(
+            Irq~N:02,
+        )*
+    }
+});
+
+seq!(N in 0..1 {
+    struct S {
+        f~N: u32,
+    }
+    const INIT: S = S { f~N: 7 };
+});
+
+fn main() {
+    assert_eq!(SUFFIXED, ["0u8", "1u8", "2u8", "3u8"]);
+    assert_eq!(first(), "0u8 1u8 2u8 3u8");
+    seq!(N in 0..1u16 {
+        assert_eq!(type_name(N), "u16");
+    });
+
+    assert_eq!(HEX, ["0x08", "0x09", "0x0a", "0x0b"]);
+
+    assert_eq!(format!("{:?}", Irq::Irq07), "Irq07");
+    assert_eq!(format!("{:?}", Irq::Irq11), "Irq11");
+    assert_eq!(INIT.f0, 7);
+}
@@ -0,0 +1,60 @@
+// Besides integer ranges, a loop variable can walk an explicit list of types,
+// identifiers or literals: T in [u8, u16, u32]. A parenthesized tuple of
+// variables destructures a list of tuples: (T, N) in [(u8, 8), (u16, 16)].
+//
+// List values are pasted and repeated exactly like range values. Integers in
+// a list keep their own suffix and radix and can be used in #{...}.
+
+use seq::seq;
+
+trait Width {
+    const BITS: u32;
+}
+
+seq!((T, N) in [(u8, 8), (u16, 16), (u32, 32), (u64, 64)] {
+    #(
+        impl Width for T {
+            const BITS: u32 = N;
+        }
+
+        #[allow(non_upper_case_globals)]
+        const MAX_~T: T = T::MAX;
+        const BYTES_~N: usize = #{N / 8};
+    )*
+});
+
+seq!(Name in [Alpha, Beta, Gamma] {
+    enum Greek {
+        #(
+            Name,
+        )*
+    }
+
+    const NAMES: [&str; 3] = [#(stringify!(Name),)*];
+});
+
+seq!(C in [Red, Green], N in 0..2 {
+    #[allow(dead_code)]
+    enum Led {
+        #(
+            C~N,
+        )*
+    }
+});
+
+fn main() {
+    assert_eq!(<u8 as Width>::BITS, 8);
+    assert_eq!(<u64 as Width>::BITS, 64);
+    assert_eq!(MAX_u16, u16::MAX);
+    assert_eq!(BYTES_32, 4);
+
+    let order = |letter: Greek| match letter {
+        Greek::Alpha => 0,
+        Greek::Beta => 1,
+        Greek::Gamma => 2,
+    };
+    assert_eq!([Greek::Alpha, Greek::Beta, Greek::Gamma].map(order), [0, 1, 2]);
+    assert_eq!(NAMES, ["Alpha", "Beta", "Gamma"]);
+
+    let _ = [Led::Red0, Led::Red1, Led::Green0, Led::Green1];
+}
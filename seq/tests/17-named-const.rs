@@ -0,0 +1,65 @@
+// A proc macro only sees tokens, so in seq!(N in 0..NPROC { ... }) it cannot
+// know how many times to repeat the body. For the common case of an array
+// initializer, seq! falls back to filling the array with a loop instead of
+// unrolling it. N is a usize in the element expression, and the loop runs
+// fine in the initializer of a const or static.
+//
+// The range ends can also be const generic parameters, as in
+// seq!(I in 0..L { ... }) inside fn f<const L: usize>(). Unless the range is
+// 0..END, the array's length is taken from where the array goes, so it needs
+// a known type there; a range of a different length is an error.
+//
+// Pasting, #{...}, #len and nested repetitions need the value at expansion time
+// and so still require integer literals.
+
+use seq::seq;
+
+const NPROC: usize = 64;
+
+mod config {
+    pub const FIRST: usize = 8;
+}
+
+#[derive(Debug, PartialEq)]
+struct Proc {
+    id: usize,
+}
+
+impl Proc {
+    const fn new(id: usize) -> Self {
+        Proc { id }
+    }
+}
+
+static PROCS: [Proc; NPROC] = seq!(N in 0..NPROC { [#(Proc::new(N),)*] });
+
+const IDS: [usize; 3] = seq!(N in config::FIRST..=10 { [#(N * 2),*] });
+
+fn doubled<const L: usize>() -> [usize; L] {
+    seq!(I in 0..L { [#(I * 2,)*] })
+}
+
+fn window<const FROM: usize, const TO: usize, const LEN: usize>() -> [usize; LEN] {
+    seq!(I in FROM..TO { [#(I,)*] })
+}
+
+const fn squares<const L: usize>() -> [usize; L] {
+    seq!(I in 0..L { [#(I * I),*] })
+}
+
+fn main() {
+    assert_eq!(PROCS[37], Proc::new(37));
+    assert_eq!(IDS, [16, 18, 20]);
+
+    let names: [String; NPROC] = seq!(N in 0..NPROC { [#(format!("proc{}", N),)*] });
+    assert_eq!(names[63], "proc63");
+
+    assert_eq!(doubled::<4>(), [0, 2, 4, 6]);
+    assert_eq!(window::<3, 6, 3>(), [3, 4, 5]);
+    const SQUARES: [usize; 5] = squares();
+    assert_eq!(SQUARES, [0, 1, 4, 9, 16]);
+
+    std::panic::set_hook(Box::new(|_| {}));
+    let mismatched = std::panic::catch_unwind(window::<3, 6, 2>);
+    assert!(mismatched.is_err());
+}
@@ -0,0 +1,15 @@
+// With a named constant as range end, seq! can only fill an array. Any other
+// body, or one that needs the loop variable's value at expansion time, is
+// reported.
+
+use seq::seq;
+
+const NPROC: usize = 4;
+
+seq!(N in 0..NPROC {
+    fn f~N() {}
+});
+
+const NAMES: [u8; NPROC] = seq!(N in 0..NPROC { [#(Irq~N,)*] });
+
+fn main() {}
@@ -0,0 +1,44 @@
+// A separator between the closing parenthesis and the star of a repetition is
+// placed between the repeated sections, as in macro_rules: #( ... ),* gives
+// no trailing comma, and #( ... )+* joins the sections into a sum.
+//
+// #len, or #count, expands to the number of repetitions, which is handy for
+// declaring an array's length next to its initializer. Inside a repetition it
+// counts the repetitions of the variables that are not bound yet.
+
+use seq::seq;
+
+seq!(N in 0..4 {
+    const SQUARES: [u32; #len] = [#(N * N),*];
+    const SUM: u32 = #(N)+*;
+    const COUNT: usize = #count;
+
+    fn all_set(flags: [bool; #len]) -> bool {
+        #(flags[N])&&*
+    }
+});
+
+seq!(R in 0..2, C in 0..3 {
+    const CELLS: usize = #len;
+    // this section binds both R and C, so nothing is left to count
+    const INNERMOST: [usize; 6] = [#( #len, )*];
+
+    #(
+        fn row~R() -> [(u8, u8); #len] {
+            [#((R, C)),*]
+        }
+    )*
+});
+
+fn main() {
+    assert_eq!(SQUARES, [0, 1, 4, 9]);
+    assert_eq!(SUM, 6);
+    assert_eq!(COUNT, 4);
+    assert!(all_set([true; 4]));
+    assert!(!all_set([true, false, true, true]));
+
+    assert_eq!(CELLS, 6);
+    assert_eq!(INNERMOST, [1; 6]);
+    assert_eq!(row0(), [(0, 0), (0, 1), (0, 2)]);
+    assert_eq!(row1(), [(1, 0), (1, 1), (1, 2)]);
+}
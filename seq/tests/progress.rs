@@ -1,13 +1,23 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse-header.rs");
-    //t.pass("tests/02-parse-body.rs");
-    //t.compile_fail("tests/03-expand-four-errors.rs");
-    //t.pass("tests/04-paste-ident.rs");
-    //t.pass("tests/05-repeat-section.rs");
-    //t.pass("tests/06-init-array.rs");
-    //t.pass("tests/07-inclusive-range.rs");
-    //t.compile_fail("tests/08-ident-span.rs");
-    //t.pass("tests/09-interaction-with-macrorules.rs");
+    t.pass("tests/01-parse-header.rs");
+    t.pass("tests/02-parse-body.rs");
+    t.compile_fail("tests/03-expand-four-errors.rs");
+    t.pass("tests/04-paste-ident.rs");
+    t.pass("tests/05-repeat-section.rs");
+    t.pass("tests/06-init-array.rs");
+    t.pass("tests/07-inclusive-range.rs");
+    t.compile_fail("tests/08-ident-span.rs");
+    t.pass("tests/09-interaction-with-macrorules.rs");
+    t.pass("tests/10-step-rev.rs");
+    t.compile_fail("tests/11-invalid-range.rs");
+    t.pass("tests/12-multiple-vars.rs");
+    t.pass("tests/13-const-expr.rs");
+    t.compile_fail("tests/14-const-expr-overflow.rs");
+    t.pass("tests/15-literal-format.rs");
+    t.pass("tests/16-lists.rs");
+    t.pass("tests/17-named-const.rs");
+    t.compile_fail("tests/18-named-const-body.rs");
+    t.pass("tests/19-separators.rs");
 }